@@ -1,4 +1,5 @@
-use rambo_core::interactive::{InteractiveTerminal, run_direct_boost};
+use rambo_core::interactive::{InteractiveTerminal, run_killer_boost_with};
+use rambo_core::stats::{default_provider, provider_from_config};
 use rambo_core::config::load_config;
 use std::{env, process};
 
@@ -14,10 +15,15 @@ fn main() {
         }
     };
 
+    let provider = provider_from_config(&config).unwrap_or_else(|e| {
+        eprintln!("⚠️  {}，使用默认内存统计后端", e);
+        default_provider()
+    });
+
     // 检查是否有参数
     if args.len() > 1 && args[1] == "b" {
-        // 直接执行清理
-        if let Err(e) = run_direct_boost() {
+        // 直接执行 Killer 模式
+        if let Err(e) = run_killer_boost_with(&config, provider.as_ref()) {
             eprintln!("❌ 清理失败: {:?}", e);
             process::exit(1);
         }
@@ -25,7 +31,7 @@ fn main() {
     }
 
     // 正常交互模式
-    let mut terminal = InteractiveTerminal::new(config);
+    let mut terminal = InteractiveTerminal::with_provider(config, provider);
 
     if let Err(e) = terminal.run() {
         eprintln!("❌ 交互式终端发生错误: {:?}", e);
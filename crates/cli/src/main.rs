@@ -1,19 +1,28 @@
 use clap::{Parser, Subcommand};
-use rambo_core::processes::{get_all_processes, sort_and_take_processes, ProcessInfo};
-use rambo_core::release::{terminate, get_candidate_processes, boost, BoostResult};
-use rambo_core::{read_mem_stats, MemStats};
+use rambo_core::processes::{sort_and_take_processes_by, MemoryMetric, ProcessInfo, ProcessMonitor};
+use rambo_core::tree::{group_processes, sort_and_take_groups, AppGroup, ProcessGrouping};
+use rambo_core::release::{terminate_with_grace, ProcessIdentity, TerminationOutcome, get_candidate_groups, get_candidate_processes_by, CandidateCriteria, boost_with_level, execute_plan, free_memory_with, killer_boost_with, plan_boost, select_free_targets, BoostError, BoostResult, FreeReport, KillerReport, ReleaseMethod};
+use rambo_core::budget::{parse_size_mb, BudgetSelection};
+use rambo_core::relaunch::{restore, RelaunchEntry, RelaunchPolicy, RelaunchRegistry, RestoreTarget};
+use rambo_core::config::RelaunchConfig;
+use rambo_core::rules::{CompiledRule, RuleAction, RuleSet};
+use rambo_core::{MemStats, SwapStats};
+use rambo_core::leaks::{LeakDetector, LeakSuspect};
+use rambo_core::measure::ReclaimMeasurement;
+use rambo_core::stats::{provider_from_config, MemoryStatsProvider};
+use rambo_core::strategy::{BoostLevel, BoostPlan, PlanKind, StepStatus};
 use rambo_core::log_entry::{read_log_events, LogEvent, cleanup_old_logs, clear_all_logs, get_logs_size, list_log_files};
 use rambo_core::config::load_config;
 use rambo_core::daemon::{Daemon, install_launchd_agent, uninstall_launchd_agent};
-use rambo_core::security::{filter_safe_processes, require_confirmation};
+use rambo_core::security::{filter_safe_groups, filter_safe_processes, require_confirmation};
 use rambo_core::hotkey::GlobalHotkey;
 use rambo_core::config::{save_config};
-use rambo_core::interactive::{InteractiveTerminal, run_direct_boost};
-use rambo_core::version::{check_for_updates, perform_update, cleanup_old_versions};
+use rambo_core::interactive::{InteractiveTerminal, run_direct_boost_with};
+use rambo_core::version::{check_for_updates, perform_update};
 use serde::Serialize;
 use chrono::Utc;
-use std::collections::HashSet;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 use std::io::Write;
 
 #[derive(Parser)]
@@ -37,6 +46,14 @@ struct Cli {
     /// Enable process termination
     #[arg(long, global = true)]
     enable_termination: Option<bool>,
+
+    /// Override memory stats backend (auto, mach, procfs or sysinfo)
+    #[arg(long, global = true)]
+    stats_backend: Option<String>,
+
+    /// Override process backend (auto, procfs or sysinfo)
+    #[arg(long, global = true)]
+    process_backend: Option<String>,
 }
 
 #[derive(Subcommand)]
@@ -45,10 +62,20 @@ enum Commands {
     Status(StatusArgs),
     /// Free up memory by running the purge command
     Boost(BoostArgs),
+    /// Killer mode: purge, terminate safe high-memory candidates, then deep cache clean
+    Killer(KillerArgs),
     /// Suggest processes that can be terminated to free memory
     Suggest(SuggestArgs),
+    /// Sample processes over time and report steady memory growth (possible leaks)
+    Leaks(LeaksArgs),
+    /// Show swap usage and activity, and the processes with the most swapped-out memory
+    Swap(SwapArgs),
+    /// Terminate the fewest safe candidates needed to free an amount of memory, e.g. `free 4G`
+    Free(FreeArgs),
     /// Terminate a process by its PID
     Kill(KillArgs),
+    /// Relaunch processes terminated by RAM Booster (lists recorded processes without arguments)
+    Restore(RestoreArgs),
     /// Show logs for a specific day
     Log(LogArgs),
     /// Manage log files (cleanup, clear, info)
@@ -74,6 +101,18 @@ struct StatusArgs {
     /// Number of top processes to show
     #[arg(long, default_value_t = 10)]
     top: usize,
+
+    /// Refresh every N seconds until interrupted
+    #[arg(long, value_name = "SECONDS")]
+    watch: Option<u64>,
+
+    /// Memory metric to sort by: rss, pss or uss (defaults to memory_metric)
+    #[arg(long)]
+    metric: Option<MemoryMetric>,
+
+    /// Aggregate processes: process, app (helpers summed under their application) or cgroup (containers and systemd units)
+    #[arg(long)]
+    group: Option<ProcessGrouping>,
 }
 
 #[derive(Parser)]
@@ -93,15 +132,90 @@ struct KillArgs {
     force: bool,
 }
 
+#[derive(Parser)]
+struct FreeArgs {
+    /// Memory to free, e.g. 4G or 512M (plain numbers are MB)
+    #[arg(value_name = "SIZE", value_parser = parse_size_mb)]
+    target_mb: u64,
+
+    /// Output in JSON format
+    #[arg(long)]
+    json: bool,
+
+    /// Show which processes would be terminated without terminating them
+    #[arg(long)]
+    dry_run: bool,
+}
+
+#[derive(Parser)]
+struct RestoreArgs {
+    /// Relaunch the recorded process with this id
+    id: Option<u64>,
+
+    /// Relaunch processes from the most recent termination whose relaunch policy is auto
+    #[arg(long, conflicts_with = "id")]
+    last: bool,
+
+    /// Output in JSON format
+    #[arg(long)]
+    json: bool,
+}
+
 #[derive(Parser)]
 struct SuggestArgs {
     /// Output in JSON format
     #[arg(long)]
     json: bool,
 
-    /// RSS threshold in MB for a process to be considered a candidate
+    /// Memory threshold in MB (in the chosen metric) for a process to be considered a candidate
     #[arg(long, default_value_t = 50)]
     rss_threshold: u64,
+
+    /// Memory metric the threshold applies to: rss, pss or uss (defaults to memory_metric)
+    #[arg(long)]
+    metric: Option<MemoryMetric>,
+
+    /// Suggest whole groups: process, app or cgroup (defaults to group_by)
+    #[arg(long)]
+    group: Option<ProcessGrouping>,
+
+    /// Only suggest processes idle (below idle.cpu_threshold CPU) for this long, e.g. 10m; samples for the whole duration
+    #[arg(long, value_parser = humantime::parse_duration)]
+    idle_for: Option<Duration>,
+}
+
+#[derive(Parser)]
+struct LeaksArgs {
+    /// Output in JSON format (includes the sample history)
+    #[arg(long)]
+    json: bool,
+
+    /// How long to sample, e.g. 10m
+    #[arg(long, default_value = "10m", value_parser = humantime::parse_duration)]
+    duration: Duration,
+
+    /// Time between samples, e.g. 30s (defaults to leaks.sample_interval_secs)
+    #[arg(long, value_parser = humantime::parse_duration)]
+    interval: Option<Duration>,
+
+    /// Minimum growth rate in MB/hour (defaults to leaks.min_slope_mb_per_hour)
+    #[arg(long)]
+    min_slope: Option<f64>,
+}
+
+#[derive(Parser)]
+struct SwapArgs {
+    /// Output in JSON format
+    #[arg(long)]
+    json: bool,
+
+    /// Number of processes to show
+    #[arg(long, default_value_t = 10)]
+    top: usize,
+
+    /// Time between the two readings used for swap-in/out rates, e.g. 2s
+    #[arg(long, default_value = "1s", value_parser = humantime::parse_duration)]
+    interval: Duration,
 }
 
 #[derive(Parser)]
@@ -109,6 +223,45 @@ struct BoostArgs {
     /// Output in JSON format
     #[arg(long)]
     json: bool,
+
+    /// Boost level: low, medium or high (defaults to boost.default_level)
+    #[arg(long)]
+    level: Option<BoostLevel>,
+
+    /// Show the boost plan without executing anything
+    #[arg(long)]
+    dry_run: bool,
+
+    /// With --dry-run, save the plan as JSON to this file
+    #[arg(long, requires = "dry_run")]
+    save_plan: Option<PathBuf>,
+
+    /// Execute a plan saved with --save-plan (targets are re-validated first)
+    #[arg(long, conflicts_with_all = ["dry_run", "level"])]
+    plan: Option<PathBuf>,
+
+    /// Sample memory over this many milliseconds after releasing (0 = single immediate read)
+    #[arg(long)]
+    settle_ms: Option<u64>,
+}
+
+#[derive(Parser)]
+struct KillerArgs {
+    /// Output in JSON format
+    #[arg(long)]
+    json: bool,
+
+    /// Show the Killer mode plan without executing anything
+    #[arg(long)]
+    dry_run: bool,
+
+    /// With --dry-run, save the plan as JSON to this file (run it with `boost --plan`)
+    #[arg(long, requires = "dry_run")]
+    save_plan: Option<PathBuf>,
+
+    /// Sample memory over this many milliseconds after the last round (0 = single immediate read)
+    #[arg(long)]
+    settle_ms: Option<u64>,
 }
 
 #[derive(Parser)]
@@ -124,6 +277,10 @@ struct DaemonArgs {
     /// Run in foreground (don't daemonize)
     #[arg(long)]
     foreground: bool,
+
+    /// Boost level used under memory pressure (overrides boost.daemon_level; saved to config with --install)
+    #[arg(long)]
+    level: Option<BoostLevel>,
 }
 
 #[derive(Parser)]
@@ -181,10 +338,33 @@ enum LogsAction {
     List,
 }
 
+#[derive(Serialize)]
+struct SwapOutput {
+    swap: SwapStats,
+    processes: Vec<ProcessInfo>,
+}
+
+/// `rb suggest --json` 的一项：候选本身加上匹配到的规则
+#[derive(Serialize)]
+struct Suggestion<'a, T: Serialize> {
+    #[serde(flatten)]
+    candidate: &'a T,
+    rule: Option<&'a str>,
+    action: Option<RuleAction>,
+}
+
+impl<'a, T: Serialize> Suggestion<'a, T> {
+    fn new(candidate: &'a T, rule: Option<&'a CompiledRule>) -> Self {
+        Self { candidate, rule: rule.map(CompiledRule::label), action: rule.map(CompiledRule::action) }
+    }
+}
+
 #[derive(Serialize)]
 struct StatusOutput {
     mem_stats: MemStats,
     processes: Vec<rambo_core::processes::ProcessInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    apps: Option<Vec<AppGroup>>,
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -206,43 +386,102 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         config.enable_process_termination = enable;
     }
 
+    if let Some(backend) = &cli.stats_backend {
+        config.stats_backend = backend.clone();
+    }
+
+    if let Some(backend) = &cli.process_backend {
+        config.process_backend = backend.clone();
+    }
+
+    let provider = provider_from_config(&config)?;
+
     // Handle interactive mode and quick boost
     if cli.boost {
         // Quick boost mode: rb -b or rb --boost
-        return Ok(run_direct_boost()?);
+        return run_direct_boost_with(&config, provider.as_ref());
     }
 
+    let mut monitor = ProcessMonitor::from_config(&config)?;
+
     match &cli.command {
         None => {
             // No subcommand provided: start interactive terminal
-            let mut interactive = InteractiveTerminal::new(config);
-            return Ok(interactive.run()?);
+            let mut interactive = InteractiveTerminal::with_provider(config, provider);
+            return interactive.run();
         }
         Some(command) => match command {
         Commands::Status(args) => {
-            let mem_stats = read_mem_stats()?;
-            let processes = get_all_processes();
-            let top_processes = sort_and_take_processes(processes, args.top);
-
-            if args.json {
-                let output = StatusOutput {
-                    mem_stats,
-                    processes: top_processes,
-                };
-                let json_string = serde_json::to_string_pretty(&output)?;
-                println!("{}", json_string);
-            } else {
-                print_status_human(&mem_stats, &top_processes);
+            let metric = args.metric.unwrap_or(config.memory_metric);
+            monitor.set_memory_metric(metric);
+            let grouping = args.group.unwrap_or_default();
+            monitor.set_read_cgroups(grouping == ProcessGrouping::Cgroup);
+            loop {
+                let mem_stats = provider.read_mem_stats()?;
+                let processes = monitor.refresh_with_cpu();
+                let apps = group_processes(&processes, grouping, &config.release.cgroup_root)
+                    .map(|groups| sort_and_take_groups(groups, args.top, metric));
+                let top_processes = sort_and_take_processes_by(processes, args.top, metric);
+
+                if args.json {
+                    let output = StatusOutput {
+                        mem_stats,
+                        processes: top_processes,
+                        apps,
+                    };
+                    let json_string = serde_json::to_string_pretty(&output)?;
+                    println!("{}", json_string);
+                } else {
+                    if args.watch.is_some() {
+                        // 清屏并回到左上角
+                        print!("\x1B[2J\x1B[H");
+                    }
+                    print_status_human(&mem_stats, &top_processes, apps.as_deref(), metric, grouping);
 
-                // 首次使用提醒：如果快捷键未启用，提醒用户
-                if !config.hotkey.enabled {
-                    println!("\n💡 提示: 可使用 'rambo hotkey enable' 启用 Control+R 快捷键快速清理内存");
+                    // 首次使用提醒：如果快捷键未启用，提醒用户
+                    if !config.hotkey.enabled && args.watch.is_none() {
+                        println!("\n💡 提示: 可使用 'rambo hotkey enable' 启用 Control+R 快捷键快速清理内存");
+                    }
                 }
+
+                let Some(interval) = args.watch else { break };
+                std::thread::sleep(std::time::Duration::from_secs(interval.max(1)));
             }
         }
         Commands::Boost(args) => {
-            println!("Boosting memory... This may take a moment.");
-            match boost() {
+            let mut config = config.clone();
+            if let Some(settle_ms) = args.settle_ms {
+                config.release.settle_window_ms = settle_ms;
+            }
+
+            if let Some(path) = &args.plan {
+                let content = std::fs::read_to_string(path)
+                    .map_err(|e| format!("Failed to read plan {}: {}", path.display(), e))?;
+                let plan: BoostPlan = serde_json::from_str(&content)
+                    .map_err(|e| format!("Failed to parse plan {}: {}", path.display(), e))?;
+                let execution = execute_plan(provider.as_ref(), &mut monitor, &config, &plan)
+                    .map_err(|e| format!("Plan execution failed: {:?}", e))?;
+
+                if args.json {
+                    println!("{}", serde_json::to_string_pretty(&execution)?);
+                } else {
+                    for target in &execution.rejected {
+                        println!("Skipping PID {} ({}): {}", target.pid, target.name, target.reason);
+                    }
+                    print_boost_human(&execution.result);
+                }
+                return Ok(());
+            }
+
+            let level = args.level.unwrap_or(config.boost.default_level);
+            if args.dry_run {
+                let plan = plan_boost(provider.as_ref(), &mut monitor, &config, PlanKind::Level { level })
+                    .map_err(|e| format!("Failed to plan boost: {:?}", e))?;
+                return output_plan(&plan, args.json, args.save_plan.as_deref());
+            }
+
+            println!("Boosting memory ({} level)... This may take a moment.", level);
+            match boost_with_level(provider.as_ref(), &mut monitor, &config, level) {
                 Ok(boost_result) => {
                     if args.json {
                         let json_string = serde_json::to_string_pretty(&boost_result)?;
@@ -261,6 +500,11 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 }
                 Err(e) => {
                     match e {
+                        rambo_core::release::BoostError::Purge(rambo_core::release::PurgeError::CommandNotFound) if cfg!(target_os = "linux") => {
+                            eprintln!("Error: {}/sys/vm/drop_caches not found.", config.release.procfs_root.display());
+                            eprintln!("Please make sure procfs is mounted and try again.");
+                            std::process::exit(1);
+                        }
                         rambo_core::release::BoostError::Purge(rambo_core::release::PurgeError::CommandNotFound) => {
                             eprintln!("Error: /usr/sbin/purge command not found.");
                             eprintln!("Please install Xcode Command Line Tools and try again.");
@@ -316,24 +560,83 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 }
             }
         }
+        Commands::Killer(args) => {
+            let mut config = config.clone();
+            if let Some(settle_ms) = args.settle_ms {
+                config.release.settle_window_ms = settle_ms;
+            }
+
+            if args.dry_run {
+                let plan = plan_boost(provider.as_ref(), &mut monitor, &config, PlanKind::Killer)
+                    .map_err(|e| format!("Failed to plan Killer mode: {:?}", e))?;
+                return output_plan(&plan, args.json, args.save_plan.as_deref());
+            }
+            if !args.json {
+                println!("Running Killer mode (3 rounds)... This may take a moment.");
+            }
+            match killer_boost_with(provider.as_ref(), &mut monitor, &config) {
+                Ok(report) => {
+                    if args.json {
+                        println!("{}", serde_json::to_string_pretty(&report)?);
+                    } else {
+                        print_killer_human(&report);
+                    }
+                }
+                Err(e) => {
+                    return Err(format!("Killer mode failed: {:?}", e).into());
+                }
+            }
+        }
         Commands::Suggest(args) => {
-            let all_processes = get_all_processes();
+            let metric = args.metric.unwrap_or(config.memory_metric);
+            monitor.set_memory_metric(metric);
 
             // Use threshold from CLI args or config
-            let threshold = if args.rss_threshold != 50 {
-                args.rss_threshold
-            } else {
-                config.rss_threshold_mb
-            };
+            let mut criteria = CandidateCriteria::from_config(&config);
+            if args.rss_threshold != 50 {
+                criteria.threshold_mb = args.rss_threshold;
+            }
+            criteria.metric = metric;
+            if let Some(idle_for) = args.idle_for {
+                criteria.idle_for = Some(idle_for);
+            }
+
+            let grouping = args.group.unwrap_or(config.group_by);
+            monitor.set_read_cgroups(grouping == ProcessGrouping::Cgroup);
+            let mut all_processes = monitor.refresh();
+            if let Some(idle_for) = criteria.idle_for {
+                // 空闲时长需要在整个窗口内持续观察 CPU 使用率
+                let interval = Duration::from_secs(config.idle.sample_interval_secs.max(1));
+                let started = Instant::now();
+                eprintln!("Sampling CPU usage for {} to find idle processes...", humantime::format_duration(idle_for));
+                while started.elapsed() < idle_for {
+                    std::thread::sleep(interval.min(idle_for.saturating_sub(started.elapsed())));
+                    all_processes = monitor.refresh();
+                }
+            }
 
-            let whitelist: HashSet<String> = config.whitelist_processes.iter().cloned().collect();
-            let blacklist: HashSet<String> = config.blacklist_processes.iter().cloned().collect();
+            let rules = RuleSet::from_config(&config)?;
 
-            let candidates = get_candidate_processes(
+            if let Some(groups) = group_processes(&all_processes, grouping, &config.release.cgroup_root) {
+                let candidates: Vec<AppGroup> = get_candidate_groups(&groups, &criteria, &rules)
+                    .into_iter()
+                    .cloned()
+                    .collect();
+                let safe_groups = filter_safe_groups(&candidates, false);
+
+                if args.json {
+                    let suggestions: Vec<_> = safe_groups.iter().map(|&g| Suggestion::new(g, rules.matching_group(g))).collect();
+                    println!("{}", serde_json::to_string_pretty(&suggestions)?);
+                } else {
+                    print_suggest_apps_human(&safe_groups, metric, grouping, &rules);
+                }
+                return Ok(());
+            }
+
+            let candidates = get_candidate_processes_by(
                 &all_processes,
-                threshold,
-                &whitelist,
-                &blacklist,
+                &criteria,
+                &rules,
             );
 
             // Apply additional safety filtering - convert back to owned processes first
@@ -341,10 +644,93 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             let safe_candidates = filter_safe_processes(&candidate_processes, false); // Only show safe processes
 
             if args.json {
-                let json_string = serde_json::to_string_pretty(&safe_candidates)?;
+                let suggestions: Vec<_> = safe_candidates.iter().map(|&p| Suggestion::new(p, rules.matching(p))).collect();
+                let json_string = serde_json::to_string_pretty(&suggestions)?;
                 println!("{}", json_string);
             } else {
-                print_suggest_human(&safe_candidates);
+                print_suggest_human(&safe_candidates, metric, &rules);
+            }
+        }
+        Commands::Swap(args) => {
+            // 速率来自相邻两次读数
+            provider.read_mem_stats()?;
+            std::thread::sleep(args.interval);
+            let swap = provider.read_mem_stats()?.swap;
+
+            let mut processes: Vec<ProcessInfo> = monitor.refresh()
+                .into_iter()
+                .filter(|p| p.swap_mb.is_some_and(|mb| mb > 0))
+                .collect();
+            processes.sort_by_key(|p| std::cmp::Reverse(p.swap_mb));
+            processes.truncate(args.top);
+
+            if args.json {
+                println!("{}", serde_json::to_string_pretty(&SwapOutput { swap, processes })?);
+            } else {
+                print_swap_human(&swap, &processes);
+            }
+        }
+        Commands::Leaks(args) => {
+            let mut leak_config = config.leaks.clone();
+            if let Some(interval) = args.interval {
+                leak_config.sample_interval_secs = interval.as_secs().max(1);
+            }
+            if let Some(min_slope) = args.min_slope {
+                leak_config.min_slope_mb_per_hour = min_slope;
+            }
+            let interval = Duration::from_secs(leak_config.sample_interval_secs.max(1));
+            // 在窗口内至少凑够 min_samples 次采样
+            let samples = (args.duration.as_secs() / interval.as_secs()).max(1) as usize + 1;
+            if samples < leak_config.min_samples {
+                leak_config.min_samples = samples.max(2);
+            }
+
+            let mut detector = LeakDetector::new(leak_config);
+            eprintln!("Sampling memory every {} for {} to find growing processes...",
+                      humantime::format_duration(interval), humantime::format_duration(args.duration));
+            for i in 0..samples {
+                if i > 0 {
+                    std::thread::sleep(interval);
+                }
+                detector.observe(&monitor.refresh());
+            }
+
+            let suspects = detector.suspects();
+            if args.json {
+                println!("{}", serde_json::to_string_pretty(&suspects)?);
+            } else {
+                print_leaks_human(&suspects);
+            }
+        }
+        Commands::Free(args) => {
+            if args.dry_run {
+                let selection = select_free_targets(&mut monitor, &config, args.target_mb);
+                if args.json {
+                    println!("{}", serde_json::to_string_pretty(&selection)?);
+                } else {
+                    print_budget_selection_human(&selection);
+                }
+                return Ok(());
+            }
+            if !config.enable_process_termination {
+                eprintln!("Process termination is disabled in configuration.");
+                eprintln!("To enable, set enable_process_termination = true in config, or use --dry-run to preview.");
+                std::process::exit(1);
+            }
+            match free_memory_with(provider.as_ref(), &mut monitor, &config, args.target_mb) {
+                Ok(report) => {
+                    if args.json {
+                        println!("{}", serde_json::to_string_pretty(&report)?);
+                    } else {
+                        print_free_human(&report);
+                    }
+                }
+                Err(BoostError::Precondition(reason)) => {
+                    println!("Nothing to terminate: {}.", reason);
+                }
+                Err(e) => {
+                    return Err(format!("Failed to free memory: {:?}", e).into());
+                }
             }
         }
         Commands::Kill(args) => {
@@ -356,19 +742,38 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             }
 
             // Find the process to get its info for safety checking
-            let all_processes = get_all_processes();
+            let all_processes = monitor.refresh();
             let target_process = all_processes.iter().find(|p| p.pid == args.pid);
 
             match target_process {
                 Some(process) => {
+                    if let Some(rule) = RuleSet::from_config(&config)?.matching(process).filter(|r| r.action() == RuleAction::Protect) {
+                        eprintln!("Process {} ({}) is protected by rule '{}'.", args.pid, process.name, rule.label());
+                        std::process::exit(1);
+                    }
                     // Use security module for confirmation
+                    // 在等待确认前记录身份，确认后 PID 若已被其他进程复用则拒绝发送信号
+                    let identity = ProcessIdentity::of(process);
                     if require_confirmation(process) {
                         println!("Terminating process {}...", args.pid);
-                        let success = terminate(args.pid, args.force);
-                        if success {
-                            println!("Process {} terminated successfully.", args.pid);
-                        } else {
-                            eprintln!("Failed to terminate process {}. It might not exist or you may not have permission.", args.pid);
+                        let relaunch = RelaunchRegistry::from_config(&config.relaunch).map(|registry| {
+                            let entry = registry.capture(process);
+                            (registry, entry)
+                        });
+                        let outcome = terminate_with_grace(&identity, args.force, config.release.terminate_grace());
+                        match outcome {
+                            TerminationOutcome::ExitedAfterTerm => println!("Process {} exited after SIGTERM.", args.pid),
+                            TerminationOutcome::Killed => println!("Process {} did not exit within the grace period and was killed.", args.pid),
+                            TerminationOutcome::PermissionDenied => eprintln!("Permission denied: cannot signal process {}.", args.pid),
+                            TerminationOutcome::NotFound => eprintln!("Process {} no longer exists.", args.pid),
+                            TerminationOutcome::StillAlive => eprintln!("Process {} is still running after the grace period. Use --force to send SIGKILL.", args.pid),
+                            TerminationOutcome::IdentityChanged => eprintln!("Refusing to signal PID {}: it now belongs to a different process.", args.pid),
+                        }
+                        if let Some((registry, entry)) = relaunch.filter(|_| outcome.is_terminated()) {
+                            match registry.record(vec![entry]) {
+                                Ok(ids) => println!("Recorded for relaunch; run `rb restore {}` to start it again.", ids[0]),
+                                Err(e) => eprintln!("Warning: {}", e),
+                            }
                         }
                     } else {
                         println!("Termination cancelled.");
@@ -379,6 +784,36 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 }
             }
         }
+        Commands::Restore(args) => {
+            let registry = RelaunchRegistry::from_config(&config.relaunch)
+                .ok_or("Relaunch registry is disabled (relaunch.enabled = false) or no data directory was found")?;
+            let target = match (args.id, args.last) {
+                (Some(id), _) => RestoreTarget::Id(id),
+                (None, true) => RestoreTarget::Last,
+                (None, false) => {
+                    let entries = registry.load()?;
+                    if args.json {
+                        println!("{}", serde_json::to_string_pretty(&entries)?);
+                    } else {
+                        print_relaunch_entries_human(&entries, &config.relaunch);
+                    }
+                    return Ok(());
+                }
+            };
+            let results = restore(&registry, &config.relaunch, target)?;
+            if args.json {
+                println!("{}", serde_json::to_string_pretty(&results)?);
+            } else if results.is_empty() {
+                println!("No terminated processes recorded.");
+            } else {
+                for r in &results {
+                    match (r.pid, &r.error) {
+                        (Some(pid), _) => println!("#{} {}: restarted as PID {}", r.id, r.name, pid),
+                        (None, error) => println!("#{} {}: not restarted ({})", r.id, r.name, error.as_deref().unwrap_or("unknown error")),
+                    }
+                }
+            }
+        }
         Commands::Log(args) => {
             let events = read_log_events(&args.date)?;
             if events.is_empty() {
@@ -518,10 +953,14 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             println!("Throttle Interval: {} seconds", config.throttle_interval_seconds);
             println!("Whitelist: {:?}", config.whitelist_processes);
             println!("Blacklist: {:?}", config.blacklist_processes);
+            println!("Rules: {}", config.rules.len());
+            for (i, rule) in config.rules.iter().enumerate() {
+                println!("  {}: {}", rule.label.clone().unwrap_or_else(|| format!("rules[{}]", i)), rule.action);
+            }
 
             // 3. Check for permissions
             println!("\n--- Permissions ---");
-            check_permissions();
+            check_permissions(provider.as_ref());
 
             // 4. Check sudo permissions for memory cleaning
             println!("\n--- Memory Cleaning Permissions ---");
@@ -552,7 +991,15 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             println!("\nDoctor check complete.");
         }
         Commands::Daemon(args) => {
+            let mut config = config.clone();
+            if let Some(level) = args.level {
+                config.boost.daemon_level = level;
+            }
+
             if args.install {
+                if args.level.is_some() {
+                    save_config(&config)?;
+                }
                 match install_launchd_agent(&config) {
                     Ok(()) => {
                         println!("LaunchAgent installed successfully.");
@@ -583,7 +1030,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                     println!("Logs will be written to ~/Library/Logs/rambo-daemon.log");
                 }
 
-                let mut daemon = Daemon::new(config);
+                let mut daemon = Daemon::with_provider(config, provider.clone());
                 if let Err(e) = daemon.run() {
                     eprintln!("Daemon failed: {}", e);
                     std::process::exit(1);
@@ -601,7 +1048,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                             println!("✅ 全局快捷键已启用");
                             println!("🎹 组合键: Control+R");
                             println!("💡 功能: 快速执行内存清理");
-                            println!("");
+                            println!();
                             println!("📋 重要提醒:");
                             println!("   1. 需要在「系统设置 > 隐私与安全性 > 辅助功能」中授权");
                             println!("   2. 运行 'rambo daemon' 或 'rambo daemon --install' 以启用后台监听");
@@ -705,11 +1152,11 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
-fn check_permissions() {
+fn check_permissions(provider: &dyn MemoryStatsProvider) {
 
     // Check if we can read memory stats
-    match rambo_core::read_mem_stats() {
-        Ok(_) => println!("[✓] Memory statistics access: OK"),
+    match provider.read_mem_stats() {
+        Ok(_) => println!("[✓] Memory statistics access: OK (backend: {})", provider.name()),
         Err(e) => {
             println!("[✗] Memory statistics access failed: {}", e);
             println!("    ➔ This may require additional permissions on some systems");
@@ -777,7 +1224,7 @@ fn check_launchd_agent_status() {
 
         // Check if the agent is loaded
         let output = Command::new("launchctl")
-            .args(&["list", "com.rambo.daemon"])
+            .args(["list", "com.rambo.daemon"])
             .output();
 
         match output {
@@ -789,11 +1236,11 @@ fn check_launchd_agent_status() {
 
                         // Try to parse the PID from the output
                         for line in stdout.lines() {
-                            if let Ok(pid) = line.trim().parse::<u32>() {
-                                if pid > 0 {
-                                    println!("    ➔ Running with PID: {}", pid);
-                                    break;
-                                }
+                            if let Ok(pid) = line.trim().parse::<u32>()
+                                && pid > 0
+                            {
+                                println!("    ➔ Running with PID: {}", pid);
+                                break;
                             }
                         }
                     } else {
@@ -823,23 +1270,23 @@ fn print_logs_human(events: &[LogEvent]) {
         if event.delta_mb != 0 {
             println!("  Delta: {} MB", event.delta_mb);
         }
-        if let Some(details) = event.details.as_object() {
-            if !details.is_empty() {
-                println!("  Details: {}", serde_json::to_string(details).unwrap_or_default());
-            }
+        if let Some(details) = event.details.as_object()
+            && !details.is_empty()
+        {
+            println!("  Details: {}", serde_json::to_string(details).unwrap_or_default());
         }
     }
 }
 
-fn print_suggest_human(candidates: &[&rambo_core::processes::ProcessInfo]) {
+fn print_suggest_human(candidates: &[&rambo_core::processes::ProcessInfo], metric: MemoryMetric, rules: &RuleSet) {
     if candidates.is_empty() {
         println!("No candidate processes found to terminate.");
         return;
     }
 
     println!("--- Candidate Processes to Terminate ---");
-    println!("{:<6} {:<25} {:>10}", "PID", "Name", "RSS (MB)");
-    println!("{:-<6} {:-<25} {:->10}", "", "", "");
+    println!("{:<6} {:<25} {:>10} {:>8}  Rule", "PID", "Name", format!("{} (MB)", metric.label()), "Idle");
+    println!("{:-<6} {:-<25} {:->10} {:->8}  {:-<4}", "", "", "", "", "");
 
     for p in candidates {
         let name = if p.name.len() > 23 {
@@ -847,13 +1294,34 @@ fn print_suggest_human(candidates: &[&rambo_core::processes::ProcessInfo]) {
         } else {
             p.name.clone()
         };
-        println!("{:<6} {:<25} {:>10}", p.pid, name, p.rss_mb);
+        println!("{:<6} {:<25} {:>10} {:>8}  {}", p.pid, name, p.memory_mb(metric), format_idle(p.idle_secs), format_rule(rules.matching(p)));
     }
 }
 
 fn print_boost_human(result: &BoostResult) {
     println!("\n--- Boost Result ---");
+    match result.method {
+        Some(ReleaseMethod::Purge) => println!("  Method: purge"),
+        Some(ReleaseMethod::DropCaches { mode }) => println!("  Method: drop_caches (mode {})", mode.value()),
+        None => {}
+    }
     println!("  Time taken: {:.2}s", result.duration.as_secs_f32());
+    if result.steps.len() > 1 {
+        println!("  Steps:");
+        for step in &result.steps {
+            let status = match step.status {
+                StepStatus::Completed => format!("{:+} MB in {:.2}s", step.delta_mb, step.duration.as_secs_f32()),
+                StepStatus::Skipped => format!("skipped ({})", step.error.as_deref().unwrap_or("")),
+                StepStatus::Failed => format!("failed ({})", step.error.as_deref().unwrap_or("")),
+            };
+            println!("    {:<12} {}", step.name, status);
+            if let Some(processes) = step.details.get("processes").and_then(|v| v.as_array()) {
+                for p in processes {
+                    println!("      {:<6} {:<25} {:>6} MB", p["pid"], p["name"].as_str().unwrap_or(""), p["rss_mb"]);
+                }
+            }
+        }
+    }
     if result.delta_mb >= 0 {
         println!("  Memory freed: {} MB", result.delta_mb);
     } else {
@@ -861,20 +1329,282 @@ fn print_boost_human(result: &BoostResult) {
     }
     println!("\n  Before: {} MB free", result.before.free_mb);
     println!("  After:  {} MB free", result.after.free_mb);
+    if let Some(measurement) = &result.measurement {
+        print_measurement_human(measurement);
+    }
+}
+
+fn print_budget_selection_human(selection: &BudgetSelection) {
+    let label = selection.metric.label();
+    println!("--- Free {} MB ({}) ---", selection.target_mb, label);
+    if selection.chosen.is_empty() {
+        println!("No safe candidate processes found to terminate.");
+        return;
+    }
+    println!("{:<6} {:<25} {:>10} {:>8}", "PID", "Name", format!("{} (MB)", label), "Idle");
+    println!("{:-<6} {:-<25} {:->10} {:->8}", "", "", "", "");
+    for p in &selection.chosen {
+        println!("{:<6} {:<25} {:>10} {:>8}", p.pid, truncate_name(&p.name), p.memory_mb(selection.metric), format_idle(p.idle_secs));
+    }
+    if selection.met {
+        println!("\nExpected to free {} MB.", selection.expected_mb);
+    } else {
+        println!("\nSafe candidates only add up to {} MB; the target will not be met.", selection.expected_mb);
+    }
+}
+
+fn print_free_human(report: &FreeReport) {
+    print_budget_selection_human(&report.selection);
+    let result = &report.result;
+    for step in &result.steps {
+        if let Some(processes) = step.details.get("processes").and_then(|v| v.as_array()) {
+            for p in processes {
+                let status = p["outcome"].as_str().map(|o| o.replace('_', " ")).unwrap_or_else(|| {
+                    (if p["terminated"].as_bool().unwrap_or(false) { "terminated" } else { "failed" }).to_string()
+                });
+                println!("  {:<6} {:<25} {}", p["pid"], p["name"].as_str().unwrap_or(""), status);
+            }
+        }
+    }
+    println!("\n  Time taken: {:.2}s", result.duration.as_secs_f32());
+    println!("  Memory freed: {} MB (expected {} MB)", result.delta_mb, report.selection.expected_mb);
+    if let Some(measurement) = &result.measurement {
+        print_measurement_human(measurement);
+    }
 }
 
-fn print_status_human(mem_stats: &MemStats, processes: &[rambo_core::processes::ProcessInfo]) {
+fn print_measurement_human(measurement: &ReclaimMeasurement) {
+    println!("\n  Settling window: {:.1}s ({} samples)", measurement.window.as_secs_f32(), measurement.samples);
+    println!("  Free delta: peak {} MB, final {} MB", measurement.peak_free_delta_mb, measurement.final_free_delta_mb);
+    println!("  Available delta: {} MB", measurement.available_delta_mb);
+    let fields = &measurement.fields;
+    println!("  Breakdown: free {:+} MB, inactive {:+} MB, compressed {:+} MB", fields.free_mb, fields.inactive_mb, fields.compressed_mb);
+}
+
+fn output_plan(plan: &BoostPlan, json: bool, save_to: Option<&Path>) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(path) = save_to {
+        std::fs::write(path, serde_json::to_string_pretty(plan)?)
+            .map_err(|e| format!("Failed to save plan to {}: {}", path.display(), e))?;
+    }
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(plan)?);
+    } else {
+        print_plan_human(plan);
+        if let Some(path) = save_to {
+            println!("\nPlan saved to {}. Run it with: rambo boost --plan {}", path.display(), path.display());
+        }
+    }
+    Ok(())
+}
+
+fn print_plan_human(plan: &BoostPlan) {
+    println!("--- Boost Plan (dry run) ---");
+    match plan.kind {
+        PlanKind::Level { level } => println!("  Mode: {} level", level),
+        PlanKind::Killer => println!("  Mode: killer"),
+    }
+    println!("  Free memory now: {} MB", plan.before.free_mb);
+    println!("  Steps:");
+    for (i, strategy) in plan.strategies.iter().enumerate() {
+        let status = match &strategy.precondition {
+            None => format!("~{} MB", strategy.estimated_mb),
+            Some(reason) => format!("would be skipped ({})", reason),
+        };
+        println!("    {}. {:<12} {}", i + 1, strategy.name, status);
+        for target in &strategy.targets {
+            println!("       signal PID {:<6} {:<25} {:>6} MB  {:?}: {}",
+                     target.process.pid, target.process.name, target.process.rss_mb,
+                     target.safety.level, target.safety.reason);
+        }
+    }
+    println!("  Estimated reclaim: ~{} MB", plan.estimated_reclaim_mb);
+    println!("\nNothing was executed.");
+}
+
+fn print_killer_human(report: &KillerReport) {
+    println!("\n--- Killer Mode Report ---");
+    for round in &report.rounds {
+        let step = &round.step;
+        let status = match step.status {
+            StepStatus::Completed => format!("{:+} MB in {:.2}s", step.delta_mb, step.duration.as_secs_f32()),
+            StepStatus::Skipped => format!("skipped ({})", step.error.as_deref().unwrap_or("")),
+            StepStatus::Failed => format!("failed ({})", step.error.as_deref().unwrap_or("")),
+        };
+        println!("  Round {} [{}] {}: {}", round.round, step.name, round.title, status);
+        if let Some(processes) = step.details.get("processes").and_then(|v| v.as_array()) {
+            for p in processes {
+                let terminated = p["terminated"].as_bool().unwrap_or(false);
+                let status = match p["outcome"].as_str() {
+                    Some(outcome) => outcome.replace('_', " "),
+                    None => (if terminated { "terminated" } else { "failed" }).to_string(),
+                };
+                println!("    {:<6} {:<25} {:>6} MB  {}", p["pid"], p["name"].as_str().unwrap_or(""), p["rss_mb"], status);
+            }
+        }
+    }
+    if !report.termination_enabled {
+        println!("  Note: process termination is disabled; set enable_process_termination = true to enable round 2.");
+    }
+    println!("  Time taken: {:.2}s", report.duration.as_secs_f32());
+    println!("  Memory freed: {} MB", report.delta_mb);
+    println!("\n  Before: {} MB free", report.before.free_mb);
+    println!("  After:  {} MB free", report.after.free_mb);
+    if let Some(measurement) = &report.measurement {
+        print_measurement_human(measurement);
+    }
+}
+
+fn print_relaunch_entries_human(entries: &[RelaunchEntry], config: &RelaunchConfig) {
+    if entries.is_empty() {
+        println!("No terminated processes recorded.");
+        return;
+    }
+    println!("{:<5} {:<20} {:<25} {:<7} {:<10} Command", "ID", "Terminated", "Name", "Policy", "Restored");
+    for e in entries.iter().rev() {
+        let policy = match config.policy_for(&e.name) {
+            RelaunchPolicy::Auto => "auto",
+            RelaunchPolicy::Manual => "manual",
+            RelaunchPolicy::Never => "never",
+        };
+        let restored = e.restored_pid.map_or_else(|| "-".to_string(), |pid| format!("PID {}", pid));
+        println!("{:<5} {:<20} {:<25} {:<7} {:<10} {}", e.id, e.terminated_at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S"),
+                 truncate_name(&e.name), policy, restored, e.cmd.join(" "));
+    }
+}
+
+fn format_swap_rates(swap: &SwapStats) -> String {
+    match (swap.swap_in_per_sec, swap.swap_out_per_sec) {
+        (Some(swap_in), Some(swap_out)) => format!(" (in {:.0} pages/s, out {:.0} pages/s)", swap_in, swap_out),
+        _ => String::new(),
+    }
+}
+
+fn print_swap_human(swap: &SwapStats, processes: &[ProcessInfo]) {
+    println!("--- Swap ---");
+    println!("  Used: {} / {} MB", swap.used_mb, swap.total_mb);
+    match (swap.swap_in_per_sec, swap.swap_out_per_sec) {
+        (Some(swap_in), Some(swap_out)) => {
+            println!("  Swap-in: {:.0} pages/s", swap_in);
+            println!("  Swap-out: {:.0} pages/s", swap_out);
+        }
+        _ => println!("  Swap-in/out rates: not available on this platform"),
+    }
+
+    if processes.is_empty() {
+        println!("\nNo processes with swapped-out memory found (per-process swap is only reported on Linux).");
+        return;
+    }
+
+    println!("\n--- Top {} Processes (by swap) ---", processes.len());
+    println!("{:<6} {:<25} {:>10} {:>10}", "PID", "Name", "Swap (MB)", "RSS (MB)");
+    println!("{:-<6} {:-<25} {:->10} {:->10}", "", "", "", "");
+    for p in processes {
+        println!("{:<6} {:<25} {:>10} {:>10}", p.pid, truncate_name(&p.name), p.swap_mb.unwrap_or(0), p.rss_mb);
+    }
+}
+
+fn print_leaks_human(suspects: &[LeakSuspect]) {
+    if suspects.is_empty() {
+        println!("No processes with steady memory growth found.");
+        return;
+    }
+
+    println!("--- Possible Memory Leaks ---");
+    println!("{:<6} {:<25} {:>10} {:>10} {:>12}", "PID", "Name", "RSS (MB)", "Growth", "MB/hour");
+    println!("{:-<6} {:-<25} {:->10} {:->10} {:->12}", "", "", "", "", "");
+
+    for s in suspects {
+        let rss_mb = s.samples.last().map_or(0, |sample| sample.rss_mb);
+        println!("{:<6} {:<25} {:>10} {:>10} {:>12.1}", s.pid, truncate_name(&s.name), rss_mb, format!("+{}", s.growth_mb), s.slope_mb_per_hour);
+    }
+}
+
+fn print_suggest_apps_human(groups: &[&AppGroup], metric: MemoryMetric, grouping: ProcessGrouping, rules: &RuleSet) {
+    let (plural, column) = match grouping {
+        ProcessGrouping::Cgroup => ("Cgroups", "Cgroup"),
+        _ => ("Applications", "Application"),
+    };
+    if groups.is_empty() {
+        println!("No candidate {} found to terminate.", plural.to_lowercase());
+        return;
+    }
+
+    println!("--- Candidate {} to Terminate ---", plural);
+    println!("{:<6} {:<25} {:>10} {:>6} {:>8}  Rule", "PID", column, format!("{} (MB)", metric.label()), "Procs", "Idle");
+    println!("{:-<6} {:-<25} {:->10} {:->6} {:->8}  {:-<4}", "", "", "", "", "", "");
+
+    for g in groups {
+        println!("{:<6} {:<25} {:>10} {:>6} {:>8}  {}", g.root_pid, truncate_name(&g.name), g.memory_mb(metric), g.processes.len(), format_idle(g.idle_secs), format_rule(rules.matching_group(g)));
+    }
+}
+
+fn format_rule(rule: Option<&CompiledRule>) -> String {
+    match rule {
+        Some(rule) => format!("{} ({})", rule.label(), rule.action()),
+        None => "-".to_string(),
+    }
+}
+
+fn format_idle(idle_secs: Option<u64>) -> String {
+    match idle_secs {
+        Some(secs) if secs >= 60 => format!("{}m", secs / 60),
+        Some(secs) if secs > 0 => format!("{}s", secs),
+        _ => "-".to_string(),
+    }
+}
+
+fn truncate_name(name: &str) -> String {
+    if name.chars().count() > 23 {
+        format!("{}...", name.chars().take(23).collect::<String>())
+    } else {
+        name.to_string()
+    }
+}
+
+fn print_status_human(mem_stats: &MemStats, processes: &[rambo_core::processes::ProcessInfo], apps: Option<&[AppGroup]>, metric: MemoryMetric, grouping: ProcessGrouping) {
     println!("--- Memory Stats ---");
     println!("  Total: {} MB", mem_stats.total_mb);
     println!("  Free: {} MB", mem_stats.free_mb);
+    println!("  Available: {} MB", mem_stats.available_mb);
     println!("  Active: {} MB", mem_stats.active_mb);
     println!("  Inactive: {} MB", mem_stats.inactive_mb);
     println!("  Wired: {} MB", mem_stats.wired_mb);
     println!("  Compressed: {} MB", mem_stats.compressed_mb);
+    if mem_stats.swap.total_mb > 0 {
+        println!("  Swap: {} / {} MB{}", mem_stats.swap.used_mb, mem_stats.swap.total_mb, format_swap_rates(&mem_stats.swap));
+    }
     println!("  Pressure: {:?}", mem_stats.pressure);
+    if let Some(psi) = &mem_stats.psi {
+        println!("  PSI some: avg10={:.2} avg60={:.2} avg300={:.2}", psi.some.avg10, psi.some.avg60, psi.some.avg300);
+        if let Some(full) = &psi.full {
+            println!("  PSI full: avg10={:.2} avg60={:.2} avg300={:.2}", full.avg10, full.avg60, full.avg300);
+        }
+    }
+    if let Some(apps) = apps {
+        if grouping == ProcessGrouping::Cgroup {
+            // cgroup 自身的统计包含页缓存和内核内存，与进程 RSS 之和分列显示
+            println!("\n--- Top {} Cgroups (by memory) ---", apps.len());
+            println!("{:<6} {:<25} {:>10} {:>10} {:>7} {:>6}", "PID", "Cgroup", format!("{} (MB)", metric.label()), "Cgroup MB", "CPU%", "Procs");
+            println!("{:-<6} {:-<25} {:->10} {:->10} {:->7} {:->6}", "", "", "", "", "", "");
+            for g in apps {
+                let current = g.cgroup_memory.as_ref().map_or("-".to_string(), |m| m.current_mb.to_string());
+                println!("{:<6} {:<25} {:>10} {:>10} {:>7.1} {:>6}", g.root_pid, truncate_name(&g.name), g.memory_mb(metric), current, g.cpu_usage, g.processes.len());
+            }
+            return;
+        }
+        println!("\n--- Top {} Applications (by memory) ---", apps.len());
+        println!("{:<6} {:<25} {:>10} {:>7} {:>6}", "PID", "Application", format!("{} (MB)", metric.label()), "CPU%", "Procs");
+        println!("{:-<6} {:-<25} {:->10} {:->7} {:->6}", "", "", "", "", "");
+        for g in apps {
+            println!("{:<6} {:<25} {:>10} {:>7.1} {:>6}", g.root_pid, truncate_name(&g.name), g.memory_mb(metric), g.cpu_usage, g.processes.len());
+        }
+        return;
+    }
+
     println!("\n--- Top {} Processes (by memory) ---", processes.len());
-    println!("{:<6} {:<25} {:>10}", "PID", "Name", "RSS (MB)");
-    println!("{:-<6} {:-<25} {:->10}", "", "", "");
+    println!("{:<6} {:<25} {:>10} {:>7}", "PID", "Name", format!("{} (MB)", metric.label()), "CPU%");
+    println!("{:-<6} {:-<25} {:->10} {:->7}", "", "", "", "");
 
     for p in processes {
         let name = if p.name.len() > 23 {
@@ -882,7 +1612,7 @@ fn print_status_human(mem_stats: &MemStats, processes: &[rambo_core::processes::
         } else {
             p.name.clone()
         };
-        println!("{:<6} {:<25} {:>10}", p.pid, name, p.rss_mb);
+        println!("{:<6} {:<25} {:>10} {:>7.1}", p.pid, name, p.memory_mb(metric), p.cpu_usage);
     }
 }
 
@@ -1,7 +1,7 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 use core::{read_mem_stats, MemStats};
 use core::release::boost;
-use core::processes::{get_all_processes, sort_and_take_processes};
+use core::processes::{get_all_processes, sort_and_take_processes, ProcessMonitor};
 use std::time::Duration;
 
 fn benchmark_memory_stats_read(c: &mut Criterion) {
@@ -21,6 +21,16 @@ fn benchmark_process_listing(c: &mut Criterion) {
         })
     });
 
+    // 长期持有的 ProcessMonitor 只做增量刷新，对比上面每次重建 System 的开销
+    c.bench_function("process_monitor_refresh", |b| {
+        let mut monitor = ProcessMonitor::new();
+        monitor.refresh();
+        b.iter(|| {
+            let processes = monitor.refresh();
+            black_box(processes)
+        })
+    });
+
     c.bench_function("sort_and_take_processes_10", |b| {
         let processes = get_all_processes();
         b.iter(|| {
@@ -77,6 +87,9 @@ fn benchmark_memory_pressure_detection(c: &mut Criterion) {
                     inactive_mb: 2000,
                     wired_mb: 2000,
                     compressed_mb: 384,
+                    available_mb: 10000,
+                    psi: None,
+                    swap: Default::default(),
                     pressure: core::PressureLevel::Normal,
                 },
                 MemStats {
@@ -86,6 +99,9 @@ fn benchmark_memory_pressure_detection(c: &mut Criterion) {
                     inactive_mb: 2000,
                     wired_mb: 3000,
                     compressed_mb: 1384,
+                    available_mb: 4000,
+                    psi: None,
+                    swap: Default::default(),
                     pressure: core::PressureLevel::Warning,
                 },
                 MemStats {
@@ -95,6 +111,9 @@ fn benchmark_memory_pressure_detection(c: &mut Criterion) {
                     inactive_mb: 1000,
                     wired_mb: 3500,
                     compressed_mb: 1384,
+                    available_mb: 1500,
+                    psi: None,
+                    swap: Default::default(),
                     pressure: core::PressureLevel::Critical,
                 },
             ];
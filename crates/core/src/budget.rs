@@ -0,0 +1,163 @@
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use serde::Serialize;
+use crate::config::Config;
+use crate::processes::{MemoryMetric, ProcessInfo};
+use crate::release::CandidateCriteria;
+use crate::rules::{CompiledRule, RuleAction, RuleSet};
+use crate::strategy::boost_candidates_by;
+
+/// 按内存预算选出的终止目标
+#[derive(Debug, Clone, Serialize)]
+pub struct BudgetSelection {
+    pub target_mb: u64,
+    pub metric: MemoryMetric,
+    /// 按选择顺序排列
+    pub chosen: Vec<ProcessInfo>,
+    /// 按 metric 估算的可回收总量
+    pub expected_mb: u64,
+    /// 估算总量是否达到目标；候选不足时为 false，chosen 为全部候选
+    pub met: bool,
+}
+
+/// 解析 `4G`、`512M`、`1.5GB` 这样的大小，返回 MB；不带单位时按 MB 计
+pub fn parse_size_mb(s: &str) -> Result<u64, String> {
+    let s = s.trim();
+    let split = s.find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(s.len());
+    let (number, unit) = s.split_at(split);
+    let value: f64 = number.parse().map_err(|_| format!("Invalid size '{}'", s))?;
+    let factor = match unit.trim().to_ascii_lowercase().as_str() {
+        "" | "m" | "mb" | "mib" => 1.0,
+        "g" | "gb" | "gib" => 1024.0,
+        "t" | "tb" | "tib" => 1024.0 * 1024.0,
+        _ => return Err(format!("Invalid size unit in '{}' (use M, G or T)", s)),
+    };
+    Ok((value * factor).round() as u64)
+}
+
+/// 从已通过安全检查的候选中选出内存合计达到 target_mb 的一组进程。
+/// 按用户优先级、空闲时长、内存大小依次排序后贪心累加，再从最不优先的开始
+/// 剔除去掉后仍能达标的进程，使终止的进程尽量少、尽量不打扰正在使用的程序
+pub fn select_for_budget(
+    candidates: &[ProcessInfo],
+    target_mb: u64,
+    metric: MemoryMetric,
+    priority: &BTreeMap<String, i32>,
+) -> BudgetSelection {
+    let mut ranked: Vec<&ProcessInfo> = candidates.iter().collect();
+    ranked.sort_by_key(|p| Reverse((
+        priority.get(&p.name).copied().unwrap_or(0),
+        p.idle_secs.unwrap_or(0),
+        p.memory_mb(metric),
+    )));
+
+    let mut chosen: Vec<&ProcessInfo> = Vec::new();
+    let mut total = 0;
+    for p in ranked {
+        if total >= target_mb {
+            break;
+        }
+        total += p.memory_mb(metric);
+        chosen.push(p);
+    }
+
+    if total >= target_mb {
+        for i in (0..chosen.len()).rev() {
+            let size = chosen[i].memory_mb(metric);
+            if total - size >= target_mb {
+                total -= size;
+                chosen.remove(i);
+            }
+        }
+    }
+
+    BudgetSelection {
+        target_mb,
+        metric,
+        chosen: chosen.into_iter().cloned().collect(),
+        expected_mb: total,
+        met: total >= target_mb,
+    }
+}
+
+/// 按 `[free]` 配置从进程表中选出要终止的进程；候选的规则、前台和安全检查与 boost 相同。
+/// 未在 priority 中列出的进程，匹配 auto_terminate 规则时优先级为 2，匹配 prefer 时为 1
+pub fn plan_free(config: &Config, processes: &[ProcessInfo], target_mb: u64) -> BudgetSelection {
+    let criteria = CandidateCriteria {
+        threshold_mb: config.free.min_process_mb,
+        metric: config.free.metric,
+        ..CandidateCriteria::from_config(config)
+    };
+    let candidates = boost_candidates_by(config, &criteria, processes);
+
+    let rules = RuleSet::from_config(config).unwrap_or_default();
+    let mut priority = config.free.priority.clone();
+    for p in &candidates {
+        let rank = match rules.matching(p).map(CompiledRule::action) {
+            Some(RuleAction::AutoTerminate) => 2,
+            Some(RuleAction::Prefer) => 1,
+            _ => continue,
+        };
+        priority.entry(p.name.clone()).or_insert(rank);
+    }
+    select_for_budget(&candidates, target_mb, config.free.metric, &priority)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(pid: u32, name: &str, pss_mb: u64, idle_secs: Option<u64>) -> ProcessInfo {
+        ProcessInfo { pid, name: name.to_string(), rss_mb: pss_mb * 2, pss_mb: Some(pss_mb), idle_secs, ..Default::default() }
+    }
+
+    fn pids(selection: &BudgetSelection) -> Vec<u32> {
+        selection.chosen.iter().map(|p| p.pid).collect()
+    }
+
+    #[test]
+    fn parses_sizes() {
+        assert_eq!(parse_size_mb("4G"), Ok(4096));
+        assert_eq!(parse_size_mb("1.5gb"), Ok(1536));
+        assert_eq!(parse_size_mb("512M"), Ok(512));
+        assert_eq!(parse_size_mb("300"), Ok(300));
+        assert!(parse_size_mb("4X").is_err());
+        assert!(parse_size_mb("G").is_err());
+    }
+
+    #[test]
+    fn picks_a_minimal_set_by_priority_idleness_and_size() {
+        let candidates = vec![
+            process(1, "editor", 3000, None),
+            process(2, "chat", 800, Some(3600)),
+            process(3, "music", 300, Some(600)),
+            process(4, "build-cache", 1500, None),
+        ];
+        let mut priority = BTreeMap::new();
+
+        // 空闲时间长的先选
+        let selection = select_for_budget(&candidates, 1000, MemoryMetric::Pss, &priority);
+        assert_eq!(pids(&selection), vec![2, 3]);
+        assert_eq!(selection.expected_mb, 1100);
+        assert!(selection.met);
+
+        // 排名靠前但不必要的进程不会被终止
+        let selection = select_for_budget(&candidates, 3000, MemoryMetric::Pss, &priority);
+        assert_eq!(pids(&selection), vec![1]);
+
+        priority.insert("build-cache".to_string(), 10);
+        let selection = select_for_budget(&candidates, 1200, MemoryMetric::Pss, &priority);
+        assert_eq!(pids(&selection), vec![4]);
+        assert_eq!(selection.expected_mb, 1500);
+
+        // RSS 口径下数值翻倍
+        assert_eq!(select_for_budget(&candidates, 1200, MemoryMetric::Rss, &priority).expected_mb, 3000);
+
+        let selection = select_for_budget(&candidates, 10_000, MemoryMetric::Pss, &priority);
+        assert!(!selection.met);
+        assert_eq!(selection.chosen.len(), 4);
+        assert_eq!(selection.expected_mb, 5600);
+
+        assert!(select_for_budget(&candidates, 0, MemoryMetric::Pss, &priority).chosen.is_empty());
+    }
+}
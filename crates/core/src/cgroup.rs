@@ -0,0 +1,204 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use crate::processes::ProcessInfo;
+use crate::tree::AppGroup;
+
+const BYTES_PER_MB: u64 = 1024 * 1024;
+
+/// 进程所属的 cgroup v2 及由路径推断出的容器或 systemd unit
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CgroupInfo {
+    /// 相对 cgroup 挂载点的路径，例如 `/system.slice/docker-<id>.scope`
+    pub path: String,
+    /// Docker/Podman/containerd/CRI-O 容器 ID
+    pub container_id: Option<String>,
+    /// 最内层的 systemd service/scope（其次是 slice）
+    pub unit: Option<String>,
+}
+
+impl CgroupInfo {
+    pub fn from_path(path: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            container_id: container_id(path),
+            unit: unit_name(path),
+        }
+    }
+
+    /// 展示用名称：容器取 ID 前 12 位，其次是 unit，最后是路径本身
+    pub fn label(&self) -> String {
+        match (&self.container_id, &self.unit) {
+            (Some(id), _) => format!("container:{}", &id[..id.len().min(12)]),
+            (None, Some(unit)) => unit.clone(),
+            (None, None) => self.path.clone(),
+        }
+    }
+}
+
+/// `/proc/<pid>/cgroup` 中 cgroup v2 的路径（`0::` 开头的行）；纯 v1 系统返回 None
+pub fn parse_proc_cgroup(content: &str) -> Option<String> {
+    content.lines()
+        .find_map(|line| line.strip_prefix("0::"))
+        .map(|path| path.trim().to_string())
+}
+
+fn is_container_id(s: &str) -> bool {
+    s.len() == 64 && s.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// 路径中最内层的容器 ID：systemd 驱动的 `docker-<id>.scope`、`libpod-<id>.scope`、
+/// `cri-containerd-<id>.scope`，或 cgroupfs 驱动的 `/docker/<id>`
+pub fn container_id(path: &str) -> Option<String> {
+    path.rsplit('/').find_map(|component| {
+        let name = component.strip_suffix(".scope").unwrap_or(component);
+        let id = name.rsplit('-').next()?;
+        is_container_id(id).then(|| id.to_string())
+    })
+}
+
+/// 最内层的 `.service`/`.scope`，没有时取最内层的 `.slice`；容器 scope 不算 unit
+pub fn unit_name(path: &str) -> Option<String> {
+    let components: Vec<&str> = path.split('/').filter(|c| !c.is_empty()).collect();
+    components.iter().rev()
+        .find(|c| (c.ends_with(".service") || c.ends_with(".scope")) && container_id(c).is_none())
+        .or_else(|| components.iter().rev().find(|c| c.ends_with(".slice")))
+        .map(|c| c.to_string())
+}
+
+/// 读取 pid 的 cgroup；进程已退出或系统不支持 cgroup v2 时返回 None
+pub fn read_process_cgroup(procfs_root: &Path, pid: u32) -> Option<CgroupInfo> {
+    let content = fs::read_to_string(procfs_root.join(pid.to_string()).join("cgroup")).ok()?;
+    parse_proc_cgroup(&content).map(|path| CgroupInfo::from_path(&path))
+}
+
+/// cgroup 级别的内存统计（`memory.current` 和 `memory.stat`），包含页缓存和内核内存
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CgroupMemory {
+    pub current_mb: u64,
+    pub anon_mb: u64,
+    pub file_mb: u64,
+    pub kernel_mb: u64,
+    pub shmem_mb: u64,
+}
+
+/// `memory.stat` 中的字段（字节）
+pub fn parse_memory_stat(content: &str) -> HashMap<String, u64> {
+    content.lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once(' ')?;
+            Some((key.to_string(), value.trim().parse().ok()?))
+        })
+        .collect()
+}
+
+/// 读取 cgroup_root 下 path 的内存统计；根 cgroup 没有 `memory.current`，返回 None
+pub fn read_cgroup_memory(cgroup_root: &Path, path: &str) -> Option<CgroupMemory> {
+    let dir = cgroup_root.join(path.trim_start_matches('/'));
+    let current: u64 = fs::read_to_string(dir.join("memory.current")).ok()?.trim().parse().ok()?;
+    let stat = fs::read_to_string(dir.join("memory.stat")).map(|s| parse_memory_stat(&s)).unwrap_or_default();
+    let mb = |key: &str| stat.get(key).copied().unwrap_or(0) / BYTES_PER_MB;
+    Some(CgroupMemory {
+        current_mb: current / BYTES_PER_MB,
+        anon_mb: mb("anon"),
+        file_mb: mb("file"),
+        kernel_mb: mb("kernel").max(mb("kernel_stack") + mb("slab")),
+        shmem_mb: mb("shmem"),
+    })
+}
+
+/// 按 cgroup 聚合进程，并附上 cgroup 的内存统计；没有 cgroup 信息的进程各自成组
+pub fn group_by_cgroup(processes: &[ProcessInfo], cgroup_root: &Path) -> Vec<AppGroup> {
+    let mut order: Vec<String> = Vec::new();
+    let mut members: HashMap<String, Vec<ProcessInfo>> = HashMap::new();
+    let mut sorted: Vec<&ProcessInfo> = processes.iter().collect();
+    sorted.sort_by_key(|p| p.pid);
+    for p in sorted {
+        let key = match &p.cgroup {
+            Some(cgroup) => format!("cgroup:{}", cgroup.path),
+            None => format!("pid:{}", p.pid),
+        };
+        members.entry(key.clone()).or_insert_with(|| {
+            order.push(key);
+            Vec::new()
+        }).push(p.clone());
+    }
+
+    order.into_iter()
+        .filter_map(|key| members.remove(&key))
+        .map(|group| {
+            let cgroup = group[0].cgroup.clone();
+            let name = cgroup.as_ref().map_or_else(|| group[0].name.clone(), CgroupInfo::label);
+            let mut app = AppGroup::new(name, group);
+            app.cgroup_memory = cgroup.and_then(|c| read_cgroup_memory(cgroup_root, &c.path));
+            app
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::procfs::tests::fake_proc_root;
+
+    const ID: &str = "4f1a2b3c4d5e6f708192a3b4c5d6e7f8091a2b3c4d5e6f708192a3b4c5d6e7f8";
+
+    #[test]
+    fn derives_container_and_unit_from_path() {
+        let docker = CgroupInfo::from_path(&format!("/system.slice/docker-{}.scope", ID));
+        assert_eq!(docker.container_id.as_deref(), Some(ID));
+        assert_eq!(docker.unit.as_deref(), Some("system.slice"));
+        assert_eq!(docker.label(), "container:4f1a2b3c4d5e");
+
+        let podman = CgroupInfo::from_path(&format!("/user.slice/user-1000.slice/user@1000.service/user.slice/libpod-{}.scope/container", ID));
+        assert_eq!(podman.container_id.as_deref(), Some(ID));
+        assert_eq!(container_id(&format!("/docker/{}", ID)).as_deref(), Some(ID));
+
+        let service = CgroupInfo::from_path("/system.slice/postgresql.service");
+        assert_eq!(service.container_id, None);
+        assert_eq!(service.label(), "postgresql.service");
+        let app = CgroupInfo::from_path("/user.slice/user-1000.slice/user@1000.service/app.slice/app-gnome-firefox-4242.scope");
+        assert_eq!(app.unit.as_deref(), Some("app-gnome-firefox-4242.scope"));
+        assert_eq!(CgroupInfo::from_path("/").label(), "/");
+
+        assert_eq!(parse_proc_cgroup("12:memory:/foo\n0::/system.slice/sshd.service\n").as_deref(), Some("/system.slice/sshd.service"));
+        assert_eq!(parse_proc_cgroup("4:cpu:/\n"), None);
+    }
+
+    #[test]
+    fn groups_processes_with_cgroup_memory() {
+        let root = fake_proc_root("cgroupfs");
+        let path = format!("/system.slice/docker-{}.scope", ID);
+        let dir = root.join(path.trim_start_matches('/'));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("memory.current"), format!("{}\n", 900 * BYTES_PER_MB)).unwrap();
+        fs::write(dir.join("memory.stat"), format!("anon {}\nfile {}\nkernel {}\nshmem 0\n",
+                                                   600 * BYTES_PER_MB, 250 * BYTES_PER_MB, 50 * BYTES_PER_MB)).unwrap();
+
+        let in_container = |pid, name: &str, rss_mb| ProcessInfo {
+            pid, name: name.to_string(), rss_mb, cgroup: Some(CgroupInfo::from_path(&path)), ..Default::default()
+        };
+        let processes = vec![
+            in_container(300, "java", 500),
+            in_container(200, "tini", 1),
+            ProcessInfo { pid: 100, name: "bash".to_string(), rss_mb: 5, ..Default::default() },
+        ];
+        let groups = group_by_cgroup(&processes, &root);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].name, "bash");
+        assert_eq!(groups[0].cgroup_memory, None);
+
+        let container = &groups[1];
+        assert_eq!(container.name, "container:4f1a2b3c4d5e");
+        assert_eq!(container.root_pid, 200);
+        assert_eq!(container.rss_mb, 501);
+        let memory = container.cgroup_memory.as_ref().unwrap();
+        assert_eq!(memory.current_mb, 900);
+        assert_eq!(memory.anon_mb, 600);
+        assert_eq!(memory.file_mb, 250);
+        assert_eq!(memory.kernel_mb, 50);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -1,6 +1,15 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
+use std::time::Duration;
 use std::{env, fs};
+use crate::processes::MemoryMetric;
+use crate::psi::PsiThresholds;
+use crate::tree::ProcessGrouping;
+use crate::release::DropCachesMode;
+use crate::relaunch::RelaunchPolicy;
+use crate::rules::{Rule, RuleSet};
+use crate::strategy::{BoostAction, BoostLevel};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Config {
@@ -12,6 +21,43 @@ pub struct Config {
     pub whitelist_processes: Vec<String>,
     pub blacklist_processes: Vec<String>,
     pub hotkey: HotkeyConfig,
+    /// 内存统计后端: "auto"、"mach"、"procfs" 或 "sysinfo"
+    #[serde(default = "default_stats_backend")]
+    pub stats_backend: String,
+    /// 进程采集后端: "auto"、"sysinfo" 或 "procfs"（仅 Linux）
+    #[serde(default = "default_process_backend")]
+    pub process_backend: String,
+    /// rss_threshold_mb 和进程排序使用的内存口径；pss/uss 只有 procfs 后端提供，其他情况退回 rss
+    #[serde(default)]
+    pub memory_metric: MemoryMetric,
+    /// 候选进程的聚合方式："process" 逐个进程，"app" 把辅助进程归并到应用并一起终止
+    #[serde(default)]
+    pub group_by: ProcessGrouping,
+    #[serde(default)]
+    pub pressure: PressureConfig,
+    #[serde(default)]
+    pub release: ReleaseConfig,
+    #[serde(default)]
+    pub boost: BoostConfig,
+    #[serde(default)]
+    pub idle: IdleConfig,
+    #[serde(default)]
+    pub leaks: LeakConfig,
+    #[serde(default)]
+    pub free: FreeConfig,
+    #[serde(default)]
+    pub relaunch: RelaunchConfig,
+    /// `[[rules]]` 候选规则，按顺序取第一条匹配的；whitelist_processes 视为 protect、blacklist_processes 视为 never_suggest，排在这些规则之后
+    #[serde(default)]
+    pub rules: Vec<Rule>,
+}
+
+fn default_stats_backend() -> String {
+    "auto".to_string()
+}
+
+fn default_process_backend() -> String {
+    "auto".to_string()
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -21,6 +67,234 @@ pub struct HotkeyConfig {
     pub show_notification: bool,
 }
 
+/// 压力等级推导设置（PSI 仅在 Linux 上可用）
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct PressureConfig {
+    pub use_psi: bool,
+    pub psi_warning_some_avg10: f64,
+    pub psi_critical_some_avg10: f64,
+    pub psi_critical_full_avg10: f64,
+    /// Daemon 在 Linux 上注册 PSI 触发器并阻塞等待，不可用时回退到定时轮询
+    pub use_psi_trigger: bool,
+    /// 触发器格式 "<some|full> <stall_us> <window_us>"；非特权进程的窗口必须是 2 秒的整数倍
+    pub psi_trigger: String,
+    /// 每秒换入页数持续高于该值时 Daemon 至少按 Warning 处理；0 表示不使用换入信号
+    pub swap_in_pages_per_sec: f64,
+    /// 换入速率需要持续的秒数
+    pub swap_in_sustained_secs: u64,
+}
+
+impl Default for PressureConfig {
+    fn default() -> Self {
+        let thresholds = PsiThresholds::default();
+        Self {
+            use_psi: true,
+            psi_warning_some_avg10: thresholds.warning_some_avg10,
+            psi_critical_some_avg10: thresholds.critical_some_avg10,
+            psi_critical_full_avg10: thresholds.critical_full_avg10,
+            use_psi_trigger: true,
+            psi_trigger: "some 150000 2000000".to_string(),
+            swap_in_pages_per_sec: 500.0,
+            swap_in_sustained_secs: 30,
+        }
+    }
+}
+
+impl PressureConfig {
+    pub fn psi_thresholds(&self) -> Option<PsiThresholds> {
+        if !self.use_psi {
+            return None;
+        }
+        Some(PsiThresholds {
+            warning_some_avg10: self.psi_warning_some_avg10,
+            critical_some_avg10: self.psi_critical_some_avg10,
+            critical_full_avg10: self.psi_critical_full_avg10,
+        })
+    }
+}
+
+/// 缓存释放设置（drop_caches 仅用于 Linux）
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct ReleaseConfig {
+    pub drop_caches_mode: DropCachesMode,
+    /// procfs 挂载点，测试时可指向临时目录
+    pub procfs_root: PathBuf,
+    /// cgroup v2 挂载点，测试时可指向临时目录
+    pub cgroup_root: PathBuf,
+    /// 清理后在该窗口内多次采样以测量回收量；默认 0，只在清理后立即读取一次（Daemon 和热键不会因此阻塞）
+    pub settle_window_ms: u64,
+    pub settle_samples: u32,
+    /// 终止进程时 SIGTERM 后等待退出的时间，超时后按 force 发送 SIGKILL
+    pub terminate_grace_ms: u64,
+}
+
+impl ReleaseConfig {
+    pub fn terminate_grace(&self) -> Duration {
+        Duration::from_millis(self.terminate_grace_ms)
+    }
+}
+
+impl Default for ReleaseConfig {
+    fn default() -> Self {
+        Self {
+            drop_caches_mode: DropCachesMode::PageCache,
+            procfs_root: PathBuf::from("/proc"),
+            cgroup_root: PathBuf::from("/sys/fs/cgroup"),
+            settle_window_ms: 0,
+            settle_samples: 4,
+            terminate_grace_ms: 2000,
+        }
+    }
+}
+
+/// 空闲进程检测
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct IdleConfig {
+    /// CPU 使用率（百分比）低于该值的采样区间计为空闲
+    pub cpu_threshold: f32,
+    /// 采样间隔；`rb suggest --idle-for` 和 Daemon 按此间隔刷新进程表
+    pub sample_interval_secs: u64,
+    /// 候选进程须持续空闲的分钟数，0 表示不按空闲筛选
+    pub min_idle_minutes: u64,
+}
+
+impl Default for IdleConfig {
+    fn default() -> Self {
+        Self {
+            cpu_threshold: 1.0,
+            sample_interval_secs: 30,
+            min_idle_minutes: 0,
+        }
+    }
+}
+
+/// 内存增长（泄漏）检测
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct LeakConfig {
+    /// RSS 拟合斜率（MB/小时）达到该值才视为疑似泄漏
+    pub min_slope_mb_per_hour: f64,
+    /// 至少需要的采样数
+    pub min_samples: usize,
+    /// 采样间隔；`rb leaks` 和 Daemon 按此间隔记录快照
+    pub sample_interval_secs: u64,
+    /// 相邻采样中不下降的最低比例，用于排除上下波动的进程
+    pub min_monotonic_ratio: f64,
+    /// 每个进程保留的最大采样数
+    pub max_samples: usize,
+    /// Daemon 是否在发现疑似泄漏时输出提醒
+    pub daemon_alerts: bool,
+}
+
+impl Default for LeakConfig {
+    fn default() -> Self {
+        Self {
+            min_slope_mb_per_hour: 100.0,
+            min_samples: 5,
+            sample_interval_secs: 60,
+            min_monotonic_ratio: 0.8,
+            max_samples: 180,
+            daemon_alerts: true,
+        }
+    }
+}
+
+/// 按内存预算选择终止目标（`rb free`）
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct FreeConfig {
+    /// 估算可回收量使用的内存口径；PSS 不会重复计算共享页
+    pub metric: MemoryMetric,
+    /// 小于该值（按 metric）的进程不参与选择
+    pub min_process_mb: u64,
+    /// 进程名（按应用聚合时为应用根进程名）到优先级的映射，越大越先被选中，未列出的为 0
+    pub priority: BTreeMap<String, i32>,
+}
+
+impl Default for FreeConfig {
+    fn default() -> Self {
+        Self {
+            metric: MemoryMetric::Pss,
+            min_process_mb: 50,
+            priority: BTreeMap::new(),
+        }
+    }
+}
+
+/// 终止进程时记录重启信息（`rb restore`）
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct RelaunchConfig {
+    pub enabled: bool,
+    /// 记录文件路径，默认为数据目录下的 `rambo/relaunch.json`
+    pub registry_path: Option<PathBuf>,
+    /// 最多保留的条目数
+    pub max_entries: usize,
+    /// 未在 policy 中列出的进程使用的策略
+    pub default_policy: RelaunchPolicy,
+    /// 进程名到重启策略的映射：auto 可由 `rb restore --last` 自动重启，manual 需指定 id，never 不允许重启
+    pub policy: BTreeMap<String, RelaunchPolicy>,
+    /// 要记录的环境变量名，`["*"]` 表示全部；默认不记录（环境中常有令牌和密钥），重启时继承当前环境
+    pub capture_env: Vec<String>,
+}
+
+impl RelaunchConfig {
+    pub fn policy_for(&self, name: &str) -> RelaunchPolicy {
+        self.policy.get(name).copied().unwrap_or(self.default_policy)
+    }
+}
+
+impl Default for RelaunchConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            registry_path: None,
+            max_entries: 200,
+            default_policy: RelaunchPolicy::Manual,
+            policy: BTreeMap::new(),
+            capture_env: Vec::new(),
+        }
+    }
+}
+
+/// 各清理等级执行的动作
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct BoostConfig {
+    /// `rb boost`、交互模式和快捷键使用的默认等级
+    pub default_level: BoostLevel,
+    /// Daemon 在内存压力下使用的等级
+    pub daemon_level: BoostLevel,
+    pub low: Vec<BoostAction>,
+    pub medium: Vec<BoostAction>,
+    pub high: Vec<BoostAction>,
+}
+
+impl Default for BoostConfig {
+    fn default() -> Self {
+        Self {
+            default_level: BoostLevel::Medium,
+            daemon_level: BoostLevel::Low,
+            low: vec![BoostAction::ReleaseCaches],
+            medium: vec![BoostAction::ReleaseCaches, BoostAction::Suggest],
+            high: vec![BoostAction::ReleaseCaches, BoostAction::TerminateCandidates],
+        }
+    }
+}
+
+impl BoostConfig {
+    pub fn actions(&self, level: BoostLevel) -> &[BoostAction] {
+        match level {
+            BoostLevel::Low => &self.low,
+            BoostLevel::Medium => &self.medium,
+            BoostLevel::High => &self.high,
+        }
+    }
+}
+
 impl Default for HotkeyConfig {
     fn default() -> Self {
         Self {
@@ -46,6 +320,18 @@ impl Default for Config {
             ],
             blacklist_processes: vec![],
             hotkey: HotkeyConfig::default(),
+            stats_backend: default_stats_backend(),
+            process_backend: default_process_backend(),
+            memory_metric: MemoryMetric::default(),
+            group_by: ProcessGrouping::default(),
+            pressure: PressureConfig::default(),
+            release: ReleaseConfig::default(),
+            boost: BoostConfig::default(),
+            idle: IdleConfig::default(),
+            leaks: LeakConfig::default(),
+            free: FreeConfig::default(),
+            relaunch: RelaunchConfig::default(),
+            rules: Vec::new(),
         }
     }
 }
@@ -119,6 +405,76 @@ pub fn load_config() -> Result<Config, String> {
         config.hotkey.key_combination = val;
     }
 
+    if let Ok(val) = env::var("RAMBO_STATS_BACKEND") {
+        config.stats_backend = val;
+    }
+
+    if let Ok(val) = env::var("RAMBO_PROCESS_BACKEND") {
+        config.process_backend = val;
+    }
+
+    if let Ok(val) = env::var("RAMBO_MEMORY_METRIC") {
+        config.memory_metric = val.parse()
+            .map_err(|_| "Invalid RAMBO_MEMORY_METRIC value")?;
+    }
+
+    if let Ok(val) = env::var("RAMBO_GROUP_BY") {
+        config.group_by = val.parse()
+            .map_err(|_| "Invalid RAMBO_GROUP_BY value")?;
+    }
+
+    if let Ok(val) = env::var("RAMBO_USE_PSI") {
+        config.pressure.use_psi = val.parse()
+            .map_err(|_| "Invalid RAMBO_USE_PSI value")?;
+    }
+
+    if let Ok(val) = env::var("RAMBO_PSI_TRIGGER") {
+        config.pressure.psi_trigger = val;
+    }
+
+    if let Ok(val) = env::var("RAMBO_DROP_CACHES_MODE") {
+        config.release.drop_caches_mode = val.parse().ok()
+            .and_then(DropCachesMode::from_value)
+            .ok_or("Invalid RAMBO_DROP_CACHES_MODE value")?;
+    }
+
+    if let Ok(val) = env::var("RAMBO_SETTLE_WINDOW_MS") {
+        config.release.settle_window_ms = val.parse()
+            .map_err(|_| "Invalid RAMBO_SETTLE_WINDOW_MS value")?;
+    }
+
+    if let Ok(val) = env::var("RAMBO_TERMINATE_GRACE_MS") {
+        config.release.terminate_grace_ms = val.parse()
+            .map_err(|_| "Invalid RAMBO_TERMINATE_GRACE_MS value")?;
+    }
+
+    if let Ok(val) = env::var("RAMBO_RELAUNCH_ENABLED") {
+        config.relaunch.enabled = val.parse()
+            .map_err(|_| "Invalid RAMBO_RELAUNCH_ENABLED value")?;
+    }
+
+    if let Ok(val) = env::var("RAMBO_SWAP_IN_THRESHOLD") {
+        config.pressure.swap_in_pages_per_sec = val.parse()
+            .map_err(|_| "Invalid RAMBO_SWAP_IN_THRESHOLD value")?;
+    }
+
+    if let Ok(val) = env::var("RAMBO_MIN_IDLE_MINUTES") {
+        config.idle.min_idle_minutes = val.parse()
+            .map_err(|_| "Invalid RAMBO_MIN_IDLE_MINUTES value")?;
+    }
+
+    if let Ok(val) = env::var("RAMBO_LEAK_MIN_SLOPE") {
+        config.leaks.min_slope_mb_per_hour = val.parse()
+            .map_err(|_| "Invalid RAMBO_LEAK_MIN_SLOPE value")?;
+    }
+
+    if let Ok(val) = env::var("RAMBO_BOOST_LEVEL") {
+        config.boost.default_level = val.parse()
+            .map_err(|_| "Invalid RAMBO_BOOST_LEVEL value")?;
+    }
+
+    RuleSet::from_config(&config)?;
+
     Ok(config)
 }
 
@@ -149,11 +505,36 @@ mod tests {
         assert!(config.whitelist_processes.contains(&"kernel_task".to_string()));
         assert!(config.whitelist_processes.contains(&"launchd".to_string()));
         assert!(config.whitelist_processes.contains(&"WindowServer".to_string()));
+        assert_eq!(config.stats_backend, "auto");
+        assert_eq!(config.process_backend, "auto");
+        assert_eq!(config.memory_metric, MemoryMetric::Rss);
+        assert_eq!(config.group_by, ProcessGrouping::Process);
+        assert!(config.pressure.use_psi);
+        assert_eq!(config.pressure.psi_thresholds(), Some(PsiThresholds::default()));
+        assert_eq!(config.release.drop_caches_mode, DropCachesMode::PageCache);
+        assert_eq!(config.release.procfs_root, PathBuf::from("/proc"));
+        assert_eq!(config.release.cgroup_root, PathBuf::from("/sys/fs/cgroup"));
+        assert_eq!(config.release.settle_window_ms, 0);
+        assert_eq!(config.boost.default_level, BoostLevel::Medium);
+        assert_eq!(config.idle.min_idle_minutes, 0);
+        assert_eq!(config.leaks.min_slope_mb_per_hour, 100.0);
+        assert_eq!(config.pressure.swap_in_pages_per_sec, 500.0);
+        assert!(config.leaks.daemon_alerts);
+        assert_eq!(config.boost.daemon_level, BoostLevel::Low);
+        assert_eq!(config.boost.actions(BoostLevel::Low), &[BoostAction::ReleaseCaches]);
+        assert!(config.boost.actions(BoostLevel::High).contains(&BoostAction::TerminateCandidates));
     }
 
     #[test]
     fn test_config_serialization() {
-        let config = Config::default();
+        let config = Config {
+            rules: vec![Rule {
+                name: Some("node*".to_string()),
+                min_idle: Some(Duration::from_secs(1800)),
+                ..Rule::exact_name("node", crate::rules::RuleAction::Prefer, "dev servers".to_string())
+            }],
+            ..Config::default()
+        };
         let toml_content = toml::to_string(&config);
         assert!(toml_content.is_ok());
 
@@ -163,6 +544,16 @@ mod tests {
 
         assert_eq!(config.rss_threshold_mb, deserialized_config.rss_threshold_mb);
         assert_eq!(config.log_backend, deserialized_config.log_backend);
+        assert_eq!(config.rules, deserialized_config.rules);
+    }
+
+    #[test]
+    fn test_boost_levels_from_toml() {
+        let boost: BoostConfig = toml::from_str("default_level = \"high\"\nhigh = [\"purge\", \"suggest\"]").unwrap();
+        assert_eq!(boost.default_level, BoostLevel::High);
+        assert_eq!(boost.actions(BoostLevel::High), &[BoostAction::ReleaseCaches, BoostAction::Suggest]);
+        // 未配置的等级保留默认动作
+        assert_eq!(boost.actions(BoostLevel::Low), &[BoostAction::ReleaseCaches]);
     }
 
     #[test]
@@ -1,41 +1,67 @@
+use std::collections::HashSet;
 use std::sync::mpsc;
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::{Duration, Instant};
 use crate::config::Config;
-use crate::release::boost;
+use crate::release::boost_with_level;
 use crate::hotkey::GlobalHotkey;
-use crate::{read_mem_stats, PressureLevel};
+use crate::leaks::LeakDetector;
+use crate::processes::ProcessMonitor;
+use crate::stats::{default_provider, provider_from_config, MemoryStatsProvider};
+use crate::{MemStats, PressureLevel};
 
 pub struct Daemon {
     config: Config,
     last_boost: Arc<Mutex<Option<Instant>>>,
     hotkey: Option<GlobalHotkey>,
+    provider: Arc<dyn MemoryStatsProvider>,
+    /// 快捷键线程和压力处理共用的进程采样器
+    monitor: Arc<Mutex<ProcessMonitor>>,
 }
 
 impl Daemon {
     pub fn new(config: Config) -> Self {
+        let provider = provider_from_config(&config).unwrap_or_else(|e| {
+            eprintln!("警告: {}，使用默认内存统计后端", e);
+            default_provider()
+        });
+        Self::with_provider(config, provider)
+    }
+
+    pub fn with_provider(config: Config, provider: Arc<dyn MemoryStatsProvider>) -> Self {
         let hotkey = if config.hotkey.enabled {
             Some(GlobalHotkey::new(config.hotkey.clone()))
         } else {
             None
         };
 
+        let monitor = ProcessMonitor::from_config(&config).unwrap_or_else(|e| {
+            eprintln!("警告: {}，使用默认进程后端", e);
+            ProcessMonitor::new()
+        });
+
         Self {
             config,
             last_boost: Arc::new(Mutex::new(None)),
             hotkey,
+            provider,
+            monitor: Arc::new(Mutex::new(monitor)),
         }
     }
 
     pub fn run(&mut self) -> Result<(), String> {
         println!("Starting RAM Booster daemon...");
-        println!("Monitoring memory pressure (throttle interval: {}s)", self.config.throttle_interval_seconds);
+        println!("Monitoring memory pressure (throttle interval: {}s, stats backend: {}, process backend: {}, boost level: {})",
+                 self.config.throttle_interval_seconds, self.provider.name(), self.monitor.lock().unwrap().backend_name(), self.config.boost.daemon_level);
 
         // Start hotkey monitoring if enabled
         if let Some(hotkey) = &self.hotkey {
             let last_boost = self.last_boost.clone();
             let throttle_interval = self.config.throttle_interval_seconds;
+            let provider = self.provider.clone();
+            let monitor = self.monitor.clone();
+            let config = self.config.clone();
 
             if let Err(e) = hotkey.start_monitoring(move || {
                 println!("🎹 快捷键 Control+R 被按下，触发内存清理...");
@@ -59,7 +85,8 @@ impl Daemon {
                 };
 
                 if should_boost {
-                    match boost() {
+                    let mut monitor = monitor.lock().unwrap();
+                    match boost_with_level(provider.as_ref(), &mut monitor, &config, config.boost.default_level) {
                         Ok(result) => {
                             // 更新last_boost时间
                             let mut last_boost_guard = last_boost.lock().unwrap();
@@ -82,12 +109,38 @@ impl Daemon {
             }
         }
 
+        // 按空闲时长筛选候选或检测内存泄漏时，定期刷新进程表以累计空闲时间和内存增长
+        let idle_enabled = self.config.idle.min_idle_minutes > 0;
+        let leaks_enabled = self.config.leaks.daemon_alerts;
+        if idle_enabled || leaks_enabled {
+            let monitor = self.monitor.clone();
+            let mut detector = leaks_enabled.then(|| LeakDetector::new(self.config.leaks.clone()));
+            let interval_secs = match (idle_enabled, leaks_enabled) {
+                (true, true) => self.config.idle.sample_interval_secs.min(self.config.leaks.sample_interval_secs),
+                (true, false) => self.config.idle.sample_interval_secs,
+                _ => self.config.leaks.sample_interval_secs,
+            };
+            let interval = Duration::from_secs(interval_secs.max(1));
+            thread::spawn(move || {
+                let mut alerted = HashSet::new();
+                loop {
+                    let processes = monitor.lock().unwrap().refresh();
+                    if let Some(detector) = detector.as_mut() {
+                        detector.observe(&processes);
+                        report_new_leak_suspects(detector, &mut alerted);
+                    }
+                    thread::sleep(interval);
+                }
+            });
+        }
+
         // Start memory pressure monitoring thread
         let (tx, rx) = mpsc::channel();
         let config = self.config.clone();
+        let provider = self.provider.clone();
 
         thread::spawn(move || {
-            memory_pressure_monitor(tx, config.throttle_interval_seconds);
+            memory_pressure_monitor(tx, provider.as_ref(), &config);
         });
 
         // Main daemon loop
@@ -127,9 +180,15 @@ impl Daemon {
     }
 
     fn handle_memory_pressure(&mut self, pressure_level: PressureLevel) {
-        println!("Memory pressure detected: {:?}", pressure_level);
+        let level = self.config.boost.daemon_level;
+        println!("Memory pressure detected: {:?}, running {} boost", pressure_level, level);
 
-        match boost() {
+        let result = {
+            let mut monitor = self.monitor.lock().unwrap();
+            boost_with_level(self.provider.as_ref(), &mut monitor, &self.config, level)
+        };
+
+        match result {
             Ok(result) => {
                 let mut last_boost_guard = self.last_boost.lock().unwrap();
                 *last_boost_guard = Some(Instant::now());
@@ -146,14 +205,134 @@ impl Daemon {
     }
 }
 
-fn memory_pressure_monitor(tx: mpsc::Sender<PressureLevel>, check_interval_secs: u64) {
-    let check_interval = Duration::from_secs(std::cmp::max(check_interval_secs / 10, 5)); // Check more frequently than boost interval
+/// 每个疑似泄漏的进程只提醒一次，直到它不再增长或退出
+fn report_new_leak_suspects(detector: &LeakDetector, alerted: &mut HashSet<u32>) {
+    let suspects = detector.suspects();
+    for suspect in suspects.iter().filter(|s| !alerted.contains(&s.pid)) {
+        println!("⚠️  疑似内存泄漏: {} (PID {}) 以 {:.0} MB/小时 增长，{} 次采样共增长 {} MB",
+                 suspect.name, suspect.pid, suspect.slope_mb_per_hour, suspect.samples.len(), suspect.growth_mb);
+    }
+    *alerted = suspects.iter().map(|s| s.pid).collect();
+}
+
+/// 只在压力等级变化时上报；持续处于 Warning/Critical 时每个 throttle 周期重报一次，
+/// 以便冷却结束后 Daemon 能再次清理
+struct PressureChangeFilter {
+    last_level: Option<PressureLevel>,
+    last_sent: Option<Instant>,
+    resend_after: Duration,
+}
+
+impl PressureChangeFilter {
+    fn new(resend_after: Duration) -> Self {
+        Self { last_level: None, last_sent: None, resend_after }
+    }
+
+    fn should_send(&mut self, level: &PressureLevel, now: Instant) -> bool {
+        let changed = self.last_level.as_ref() != Some(level);
+        let elevated = matches!(level, PressureLevel::Warning | PressureLevel::Critical);
+        let stale = self.last_sent.is_none_or(|sent| now.duration_since(sent) >= self.resend_after);
+
+        if changed || (elevated && stale) {
+            self.last_level = Some(level.clone());
+            self.last_sent = Some(now);
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// 换入速率持续超过阈值时视为内存压力：频繁换入说明工作集放不进内存，即使空闲内存看起来还够
+struct SwapInSignal {
+    threshold: f64,
+    sustained: Duration,
+    above_since: Option<Instant>,
+}
+
+impl SwapInSignal {
+    fn new(threshold: f64, sustained: Duration) -> Self {
+        Self { threshold, sustained, above_since: None }
+    }
+
+    /// 记录一次读数，返回换入是否已持续超过阈值；没有速率数据时重新计时
+    fn observe(&mut self, stats: &MemStats, now: Instant) -> bool {
+        let above = self.threshold > 0.0 && stats.swap.swap_in_per_sec.is_some_and(|rate| rate >= self.threshold);
+        if !above {
+            self.above_since = None;
+            return false;
+        }
+        let since = *self.above_since.get_or_insert(now);
+        now.duration_since(since) >= self.sustained
+    }
+
+    /// 持续换入时把 Normal 提升为 Warning
+    fn apply(&mut self, stats: &MemStats, now: Instant) -> PressureLevel {
+        if self.observe(stats, now) && stats.pressure == PressureLevel::Normal {
+            println!("Sustained swap-in ({:.0} pages/s), treating as memory pressure", stats.swap.swap_in_per_sec.unwrap_or(0.0));
+            PressureLevel::Warning
+        } else {
+            stats.pressure.clone()
+        }
+    }
+}
+
+fn memory_pressure_monitor(tx: mpsc::Sender<PressureLevel>, provider: &dyn MemoryStatsProvider, config: &Config) {
+    let throttle = Duration::from_secs(config.throttle_interval_seconds);
+    let mut filter = PressureChangeFilter::new(throttle);
+    let mut swap_signal = SwapInSignal::new(
+        config.pressure.swap_in_pages_per_sec,
+        Duration::from_secs(config.pressure.swap_in_sustained_secs),
+    );
+
+    #[cfg(target_os = "linux")]
+    if config.pressure.use_psi_trigger {
+        use crate::psi::{PsiTrigger, DEFAULT_MEMORY_PSI_PATH};
+
+        match PsiTrigger::register(std::path::Path::new(DEFAULT_MEMORY_PSI_PATH), &config.pressure.psi_trigger) {
+            Ok(trigger) => {
+                println!("Waiting for PSI trigger events ({})", config.pressure.psi_trigger);
+                // Wake up at least once per throttle interval to notice recovery
+                let heartbeat = std::cmp::max(throttle, Duration::from_secs(5));
+                loop {
+                    let stalled = match trigger.wait(heartbeat) {
+                        Ok(stalled) => stalled,
+                        Err(e) => {
+                            eprintln!("PSI trigger failed, falling back to polling: {}", e);
+                            break;
+                        }
+                    };
+
+                    let level = match provider.read_mem_stats() {
+                        // A kernel-reported stall is at least a warning even if averages lag behind
+                        Ok(stats) if stalled && stats.pressure == PressureLevel::Normal => PressureLevel::Warning,
+                        Ok(stats) => swap_signal.apply(&stats, Instant::now()),
+                        Err(e) => {
+                            eprintln!("Failed to read memory stats: {}", e);
+                            continue;
+                        }
+                    };
+
+                    if filter.should_send(&level, Instant::now()) && tx.send(level).is_err() {
+                        eprintln!("Failed to send memory pressure event - daemon may have stopped");
+                        return;
+                    }
+                }
+            }
+            Err(e) => {
+                eprintln!("PSI triggers unavailable ({}), using timer polling", e);
+            }
+        }
+    }
+
+    let check_interval = Duration::from_secs(std::cmp::max(config.throttle_interval_seconds / 10, 5)); // Check more frequently than boost interval
 
     loop {
-        match read_mem_stats() {
+        match provider.read_mem_stats() {
             Ok(stats) => {
-                // Send pressure level if it has changed significantly
-                if let Err(_) = tx.send(stats.pressure) {
+                let now = Instant::now();
+                let level = swap_signal.apply(&stats, now);
+                if filter.should_send(&level, now) && tx.send(level).is_err() {
                     eprintln!("Failed to send memory pressure event - daemon may have stopped");
                     break;
                 }
@@ -234,7 +413,7 @@ pub fn uninstall_launchd_agent() -> Result<(), String> {
     if std::path::Path::new(&plist_path).exists() {
         // First try to unload the service
         let output = Command::new("launchctl")
-            .args(&["unload", &plist_path])
+            .args(["unload", &plist_path])
             .output()
             .map_err(|e| format!("Failed to run launchctl unload: {}", e))?;
 
@@ -261,7 +440,8 @@ pub fn uninstall_launchd_agent() -> Result<(), String> {
 mod tests {
     use super::*;
     use std::time::Duration;
-    use crate::PressureLevel;
+    use crate::{PressureLevel, SwapStats};
+    use crate::stats::FakeStatsProvider;
 
     #[test]
     fn test_daemon_creation() {
@@ -301,8 +481,10 @@ mod tests {
 
     #[test]
     fn test_throttle_logic() {
-        let mut config = Config::default();
-        config.throttle_interval_seconds = 1; // Short interval for testing
+        let config = Config {
+            throttle_interval_seconds: 1, // Short interval for testing
+            ..Config::default()
+        };
         let daemon = Daemon::new(config);
 
         // First boost should be allowed
@@ -324,6 +506,69 @@ mod tests {
         assert!(daemon.should_trigger_boost(&PressureLevel::Critical));
     }
 
+    #[test]
+    fn test_daemon_with_custom_provider() {
+        let stats = MemStats {
+            total_mb: 16384,
+            free_mb: 200,
+            active_mb: 0,
+            inactive_mb: 0,
+            wired_mb: 0,
+            compressed_mb: 0,
+            available_mb: 200,
+            psi: None,
+            swap: Default::default(),
+            pressure: PressureLevel::Critical,
+        };
+        let provider = Arc::new(FakeStatsProvider::new(stats));
+        let daemon = Daemon::with_provider(Config::default(), provider);
+
+        assert_eq!(daemon.provider.name(), "fake");
+        let stats = daemon.provider.read_mem_stats().unwrap();
+        assert!(daemon.should_trigger_boost(&stats.pressure));
+    }
+
+    #[test]
+    fn test_pressure_filter_only_sends_changes() {
+        let mut filter = PressureChangeFilter::new(Duration::from_secs(300));
+        let start = Instant::now();
+
+        assert!(filter.should_send(&PressureLevel::Normal, start));
+        assert!(!filter.should_send(&PressureLevel::Normal, start + Duration::from_secs(10)));
+        assert!(!filter.should_send(&PressureLevel::Normal, start + Duration::from_secs(600)));
+
+        assert!(filter.should_send(&PressureLevel::Warning, start + Duration::from_secs(20)));
+        assert!(!filter.should_send(&PressureLevel::Warning, start + Duration::from_secs(30)));
+        // Sustained pressure is re-reported once the throttle interval has passed
+        assert!(filter.should_send(&PressureLevel::Warning, start + Duration::from_secs(320)));
+
+        assert!(filter.should_send(&PressureLevel::Critical, start + Duration::from_secs(330)));
+        assert!(filter.should_send(&PressureLevel::Normal, start + Duration::from_secs(340)));
+    }
+
+    #[test]
+    fn test_sustained_swap_in_raises_pressure() {
+        let mut signal = SwapInSignal::new(500.0, Duration::from_secs(30));
+        let start = Instant::now();
+        let swapping = |rate: Option<f64>| MemStats {
+            total_mb: 16384,
+            swap: SwapStats { swap_in_per_sec: rate, ..Default::default() },
+            ..Default::default()
+        };
+
+        assert_eq!(signal.apply(&swapping(Some(800.0)), start), PressureLevel::Normal);
+        assert_eq!(signal.apply(&swapping(Some(900.0)), start + Duration::from_secs(20)), PressureLevel::Normal);
+        assert_eq!(signal.apply(&swapping(Some(700.0)), start + Duration::from_secs(30)), PressureLevel::Warning);
+
+        // 速率回落后重新计时
+        assert_eq!(signal.apply(&swapping(Some(10.0)), start + Duration::from_secs(40)), PressureLevel::Normal);
+        assert_eq!(signal.apply(&swapping(Some(800.0)), start + Duration::from_secs(50)), PressureLevel::Normal);
+        assert_eq!(signal.apply(&swapping(None), start + Duration::from_secs(90)), PressureLevel::Normal);
+
+        let critical = MemStats { pressure: PressureLevel::Critical, ..swapping(Some(800.0)) };
+        assert_eq!(SwapInSignal::new(0.0, Duration::ZERO).apply(&critical, start), PressureLevel::Critical);
+    }
+
     #[test]
     fn test_config_clone() {
         let config = Config::default();
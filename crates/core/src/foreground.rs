@@ -0,0 +1,169 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::procfs::{parse_stat, TerminalStat};
+use crate::processes::ProcessInfo;
+
+/// 判断用户正在使用哪些进程；这些进程会被标记为 `is_frontmost`，不会成为终止候选
+pub trait ForegroundDetector: Send {
+    fn name(&self) -> &'static str;
+
+    /// 在一次进程快照中找出前台进程；terminals 是 procfs 采集器已读取的终端字段，其他后端为 None
+    fn frontmost_pids(&self, processes: &[ProcessInfo], terminals: Option<&HashMap<u32, TerminalStat>>) -> HashSet<u32>;
+}
+
+/// macOS：NSWorkspace 报告的前台应用
+#[cfg(target_os = "macos")]
+pub struct WorkspaceForeground;
+
+#[cfg(target_os = "macos")]
+impl ForegroundDetector for WorkspaceForeground {
+    fn name(&self) -> &'static str {
+        "nsworkspace"
+    }
+
+    fn frontmost_pids(&self, _processes: &[ProcessInfo], _terminals: Option<&HashMap<u32, TerminalStat>>) -> HashSet<u32> {
+        use objc2_app_kit::NSWorkspace;
+
+        let workspace = NSWorkspace::sharedWorkspace();
+        workspace.frontmostApplication()
+            .map(|app| app.processIdentifier() as u32)
+            .into_iter()
+            .collect()
+    }
+}
+
+/// Linux：各终端前台进程组（`/proc/<pid>/stat` 中 pgrp == tpgid）中的进程及其祖先，
+/// 即正在运行的命令、所在的 shell 以及终端模拟器或 sshd
+pub struct TtyForeground {
+    root: PathBuf,
+}
+
+impl TtyForeground {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+}
+
+impl ForegroundDetector for TtyForeground {
+    fn name(&self) -> &'static str {
+        "tty"
+    }
+
+    fn frontmost_pids(&self, processes: &[ProcessInfo], terminals: Option<&HashMap<u32, TerminalStat>>) -> HashSet<u32> {
+        let mut parents = HashMap::with_capacity(processes.len());
+        let mut foreground = Vec::new();
+        for p in processes {
+            // sysinfo 后端没有终端字段，才自行读取 stat
+            let terminal = match terminals {
+                Some(terminals) => terminals.get(&p.pid).copied(),
+                None => fs::read_to_string(self.root.join(p.pid.to_string()).join("stat")).ok()
+                    .and_then(|content| parse_stat(&content).ok())
+                    .map(|stat| TerminalStat::of(&stat)),
+            };
+            let Some(terminal) = terminal else { continue };
+            parents.insert(p.pid, terminal.ppid);
+            if terminal.is_foreground() {
+                foreground.push(p.pid);
+            }
+        }
+
+        let mut pids = HashSet::new();
+        for pid in foreground {
+            // 遇到已标记的进程说明其祖先也已标记
+            let mut current = pid;
+            while current > 1 && pids.insert(current) {
+                match parents.get(&current) {
+                    Some(&ppid) => current = ppid,
+                    None => break,
+                }
+            }
+        }
+        pids
+    }
+}
+
+/// 不标记任何前台进程
+pub struct NoForeground;
+
+impl ForegroundDetector for NoForeground {
+    fn name(&self) -> &'static str {
+        "none"
+    }
+
+    fn frontmost_pids(&self, _processes: &[ProcessInfo], _terminals: Option<&HashMap<u32, TerminalStat>>) -> HashSet<u32> {
+        HashSet::new()
+    }
+}
+
+/// 当前平台的默认实现；Linux 从 procfs_root 读取终端信息
+#[cfg_attr(not(target_os = "linux"), allow(unused_variables))]
+pub fn default_detector(procfs_root: &Path) -> Box<dyn ForegroundDetector> {
+    #[cfg(target_os = "macos")]
+    {
+        Box::new(WorkspaceForeground)
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        Box::new(TtyForeground::new(procfs_root))
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
+    {
+        Box::new(NoForeground)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::procfs::tests::{fake_proc_root, write_fake_process};
+
+    fn write_stat(root: &Path, pid: u32, ppid: u32, pgrp: u32, tty_nr: u32, tpgid: i32) {
+        // 其余文件供 procfs 采集器读取
+        write_fake_process(root, pid, &format!("p{}", pid), ppid, 100, 0);
+        let dir = root.join(pid.to_string());
+        fs::write(
+            dir.join("stat"),
+            format!("{} (p{}) S {} {} {} {} {} 4194304 100 0 0 0 0 0 0 0 20 0 1 0 5000 1048576 100 0\n",
+                    pid, pid, ppid, pgrp, pgrp, tty_nr, tpgid),
+        ).unwrap();
+    }
+
+    #[test]
+    fn marks_terminal_foreground_groups_and_ancestors() {
+        let root = fake_proc_root("foreground");
+        // 终端模拟器 100 → shell 200 → 前台的 vim 300；shell 的后台任务 400；守护进程 500 没有终端
+        write_stat(&root, 100, 1, 100, 0, -1);
+        write_stat(&root, 200, 100, 200, 34816, 300);
+        write_stat(&root, 300, 200, 300, 34816, 300);
+        write_stat(&root, 400, 200, 400, 34816, 300);
+        write_stat(&root, 500, 1, 500, 0, -1);
+
+        let processes: Vec<ProcessInfo> = [1, 100, 200, 300, 400, 500, 600].iter()
+            .map(|&pid| ProcessInfo { pid, ..Default::default() })
+            .collect();
+        let pids = TtyForeground::new(&root).frontmost_pids(&processes, None);
+        assert_eq!(pids, HashSet::from([100, 200, 300]));
+        assert!(NoForeground.frontmost_pids(&processes, None).is_empty());
+
+        // procfs 采集器提供的终端字段与自行读取的结果一致
+        let mut collector = crate::procfs::ProcfsCollector::new(&root);
+        let _ = collector.collect();
+        assert_eq!(collector.terminals().len(), 5);
+        let pids = TtyForeground::new("/nonexistent").frontmost_pids(&processes, Some(collector.terminals()));
+        assert_eq!(pids, HashSet::from([100, 200, 300]));
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn default_detector_reports_running_pids() {
+        let processes = crate::processes::get_all_processes();
+        let detector = default_detector(Path::new("/proc"));
+        for pid in detector.frontmost_pids(&processes, None) {
+            assert!(pid > 0);
+        }
+    }
+}
@@ -1,5 +1,4 @@
 use std::sync::mpsc::{self, Receiver, Sender};
-use std::thread;
 
 use crate::config::HotkeyConfig;
 
@@ -23,6 +22,13 @@ impl GlobalHotkey {
         self.config.enabled
     }
 
+    /// 全局快捷键依赖 CoreGraphics 事件 tap，其他平台始终返回 false
+    #[cfg(not(target_os = "macos"))]
+    pub fn check_accessibility_permission() -> bool {
+        false
+    }
+
+    #[cfg(target_os = "macos")]
     pub fn check_accessibility_permission() -> bool {
         // 检查辅助功能权限
         unsafe {
@@ -80,103 +86,112 @@ impl GlobalHotkey {
             return Ok(());
         }
 
+        if !cfg!(target_os = "macos") {
+            return Err("全局快捷键仅支持 macOS".into());
+        }
+
         if !Self::check_accessibility_permission() {
             Self::request_accessibility_permission()?;
             return Err("需要辅助功能权限".into());
         }
 
         println!("🎹 全局快捷键已启用: {}", self.config.key_combination);
+        spawn_event_tap(callback);
+        Ok(())
+    }
 
-        // 启动后台监听线程
+    pub fn stop_monitoring(&mut self) {
+        self.sender = None;
+        println!("🛑 全局快捷键监听已停止");
+    }
+}
 
-        thread::spawn(move || {
-            unsafe {
-                use std::ptr;
-                use libc::c_void;
+#[cfg(not(target_os = "macos"))]
+fn spawn_event_tap(_callback: impl Fn() + Send + 'static) {}
 
-                // 设置事件监听回调
-                extern "C" fn event_tap_callback(
-                    _proxy: *mut c_void,
-                    event_type: u32,
-                    event: *mut c_void,
-                    refcon: *mut c_void,
-                ) -> *mut c_void {
-                    const CG_EVENT_KEY_DOWN: u32 = 10;
+/// 启动后台监听线程
+#[cfg(target_os = "macos")]
+fn spawn_event_tap(callback: impl Fn() + Send + 'static) {
+    std::thread::spawn(move || {
+        unsafe {
+            use std::ptr;
+            use libc::c_void;
 
-                    if event_type == CG_EVENT_KEY_DOWN {
-                        extern "C" {
-                            fn CGEventGetIntegerValueField(event: *mut c_void, field: u32) -> i64;
-                        }
+            // 设置事件监听回调
+            extern "C" fn event_tap_callback(
+                _proxy: *mut c_void,
+                event_type: u32,
+                event: *mut c_void,
+                refcon: *mut c_void,
+            ) -> *mut c_void {
+                const CG_EVENT_KEY_DOWN: u32 = 10;
+
+                if event_type == CG_EVENT_KEY_DOWN {
+                    extern "C" {
+                        fn CGEventGetIntegerValueField(event: *mut c_void, field: u32) -> i64;
+                    }
 
-                        let keycode = unsafe { CGEventGetIntegerValueField(event, 9) }; // kCGKeyboardEventKeycode
-                        let flags = unsafe { CGEventGetIntegerValueField(event, 1) }; // kCGEventSourceFlagsField
+                    let keycode = unsafe { CGEventGetIntegerValueField(event, 9) }; // kCGKeyboardEventKeycode
+                    let flags = unsafe { CGEventGetIntegerValueField(event, 1) }; // kCGEventSourceFlagsField
 
-                        // 检查是否为 Control+R (keycode 15, Control flag 0x40000)
-                        if keycode == 15 && (flags & 0x40000) != 0 {
-                            if !refcon.is_null() {
-                                unsafe {
-                                    let callback = &*(refcon as *const Box<dyn Fn() + Send>);
-                                    callback();
-                                }
+                    // 检查是否为 Control+R (keycode 15, Control flag 0x40000)
+                    if keycode == 15 && (flags & 0x40000) != 0 {
+                        if !refcon.is_null() {
+                            unsafe {
+                                let callback = &*(refcon as *const Box<dyn Fn() + Send>);
+                                callback();
                             }
                         }
                     }
-
-                    event // 返回原始事件，不拦截
                 }
 
-                // 创建事件tap
-                extern "C" {
-                    fn CGEventTapCreate(
-                        tap: u32,
-                        place: u32,
-                        options: u32,
-                        events_of_interest: u64,
-                        callback: extern "C" fn(*mut c_void, u32, *mut c_void, *mut c_void) -> *mut c_void,
-                        refcon: *mut c_void,
-                    ) -> *mut c_void;
-
-                    fn CFRunLoopAddSource(rl: *mut c_void, source: *mut c_void, mode: *mut c_void);
-                    fn CFRunLoopRun();
-                    fn CFRunLoopGetCurrent() -> *mut c_void;
-                    fn CFMachPortCreateRunLoopSource(allocator: *mut c_void, port: *mut c_void, order: i32) -> *mut c_void;
-                    fn kCFRunLoopCommonModes() -> *mut c_void;
-                }
+                event // 返回原始事件，不拦截
+            }
 
-                let callback_box = Box::new(callback);
-                let callback_ptr = Box::into_raw(Box::new(callback_box)) as *mut c_void;
-
-                let event_tap = CGEventTapCreate(
-                    0, // kCGSessionEventTap
-                    0, // kCGHeadInsertEventTap
-                    0, // kCGEventTapOptionDefault
-                    1 << 10, // kCGEventMaskForAllEvents
-                    event_tap_callback,
-                    callback_ptr,
-                );
-
-                if event_tap.is_null() {
-                    eprintln!("❌ 无法创建全局快捷键监听 - 可能缺少辅助功能权限");
-                    return;
-                }
+            // 创建事件tap
+            extern "C" {
+                fn CGEventTapCreate(
+                    tap: u32,
+                    place: u32,
+                    options: u32,
+                    events_of_interest: u64,
+                    callback: extern "C" fn(*mut c_void, u32, *mut c_void, *mut c_void) -> *mut c_void,
+                    refcon: *mut c_void,
+                ) -> *mut c_void;
 
-                let run_loop_source = CFMachPortCreateRunLoopSource(ptr::null_mut(), event_tap, 0);
-                let run_loop = CFRunLoopGetCurrent();
+                fn CFRunLoopAddSource(rl: *mut c_void, source: *mut c_void, mode: *mut c_void);
+                fn CFRunLoopRun();
+                fn CFRunLoopGetCurrent() -> *mut c_void;
+                fn CFMachPortCreateRunLoopSource(allocator: *mut c_void, port: *mut c_void, order: i32) -> *mut c_void;
+                fn kCFRunLoopCommonModes() -> *mut c_void;
+            }
 
-                CFRunLoopAddSource(run_loop, run_loop_source, kCFRunLoopCommonModes());
+            let callback_box = Box::new(callback);
+            let callback_ptr = Box::into_raw(Box::new(callback_box)) as *mut c_void;
+
+            let event_tap = CGEventTapCreate(
+                0, // kCGSessionEventTap
+                0, // kCGHeadInsertEventTap
+                0, // kCGEventTapOptionDefault
+                1 << 10, // kCGEventMaskForAllEvents
+                event_tap_callback,
+                callback_ptr,
+            );
 
-                println!("✅ 全局快捷键监听已启动");
-                CFRunLoopRun(); // 进入事件循环
+            if event_tap.is_null() {
+                eprintln!("❌ 无法创建全局快捷键监听 - 可能缺少辅助功能权限");
+                return;
             }
-        });
 
-        Ok(())
-    }
+            let run_loop_source = CFMachPortCreateRunLoopSource(ptr::null_mut(), event_tap, 0);
+            let run_loop = CFRunLoopGetCurrent();
 
-    pub fn stop_monitoring(&mut self) {
-        self.sender = None;
-        println!("🛑 全局快捷键监听已停止");
-    }
+            CFRunLoopAddSource(run_loop, run_loop_source, kCFRunLoopCommonModes());
+
+            println!("✅ 全局快捷键监听已启动");
+            CFRunLoopRun(); // 进入事件循环
+        }
+    });
 }
 
 // 简化的按键监听函数，用于概念验证
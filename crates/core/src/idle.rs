@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use crate::processes::ProcessInfo;
+
+/// 跨多次刷新跟踪进程 CPU 使用率：每次采样覆盖上一次采样到现在的区间，
+/// 连续低于阈值的区间累加即为空闲时长。以 (pid, 启动时间) 为键，PID 复用时重新计时
+#[derive(Debug)]
+pub struct IdleTracker {
+    cpu_threshold: f32,
+    idle_since: HashMap<(u32, Option<u64>), Instant>,
+    last_sample: Option<Instant>,
+}
+
+impl IdleTracker {
+    pub fn new(cpu_threshold: f32) -> Self {
+        Self {
+            cpu_threshold,
+            idle_since: HashMap::new(),
+            last_sample: None,
+        }
+    }
+
+    pub fn set_cpu_threshold(&mut self, cpu_threshold: f32) {
+        self.cpu_threshold = cpu_threshold;
+    }
+
+    /// 记录采样区间的起点（此时的 CPU 使用率还没有意义）
+    pub fn start(&mut self, now: Instant) {
+        self.idle_since.clear();
+        self.last_sample = Some(now);
+    }
+
+    /// 是否已经有至少一个完整的采样区间
+    pub fn is_tracking(&self) -> bool {
+        self.last_sample.is_some()
+    }
+
+    /// 用一次带有效 CPU 使用率的快照更新空闲状态；已退出或变忙的进程被移除
+    pub fn observe(&mut self, processes: &[ProcessInfo], now: Instant) {
+        let interval_start = self.last_sample.unwrap_or(now);
+        let mut idle_since = HashMap::with_capacity(processes.len());
+        for p in processes.iter().filter(|p| p.cpu_usage < self.cpu_threshold) {
+            let key = (p.pid, p.start_time);
+            let since = self.idle_since.get(&key).copied().unwrap_or(interval_start);
+            idle_since.insert(key, since);
+        }
+        self.idle_since = idle_since;
+        self.last_sample = Some(now);
+    }
+
+    /// 持续空闲的时长；正在使用 CPU 时为 0，尚未开始跟踪时为 None
+    pub fn idle_for(&self, process: &ProcessInfo, now: Instant) -> Option<Duration> {
+        self.last_sample?;
+        Some(
+            self.idle_since
+                .get(&(process.pid, process.start_time))
+                .map_or(Duration::ZERO, |since| now.saturating_duration_since(*since)),
+        )
+    }
+
+    /// 把空闲时长写入 `ProcessInfo::idle_secs`
+    pub fn annotate(&self, processes: &mut [ProcessInfo], now: Instant) {
+        for p in processes.iter_mut() {
+            p.idle_secs = self.idle_for(p, now).map(|d| d.as_secs());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(pid: u32, cpu_usage: f32) -> ProcessInfo {
+        ProcessInfo { pid, name: format!("p{}", pid), cpu_usage, start_time: Some(1000), ..Default::default() }
+    }
+
+    #[test]
+    fn accumulates_idle_time_across_samples() {
+        let t0 = Instant::now();
+        let minute = Duration::from_secs(60);
+        let mut tracker = IdleTracker::new(1.0);
+        assert_eq!(tracker.idle_for(&process(1, 0.0), t0), None);
+
+        tracker.start(t0);
+        tracker.observe(&[process(1, 0.1), process(2, 35.0)], t0 + minute);
+        tracker.observe(&[process(1, 0.0), process(2, 0.2)], t0 + 2 * minute);
+
+        let now = t0 + 2 * minute;
+        assert_eq!(tracker.idle_for(&process(1, 0.0), now), Some(2 * minute));
+        assert_eq!(tracker.idle_for(&process(2, 0.0), now), Some(minute));
+
+        // 再次变忙后重新计时
+        tracker.observe(&[process(1, 5.0), process(2, 0.0)], t0 + 3 * minute);
+        let mut processes = vec![process(1, 5.0), process(2, 0.0)];
+        tracker.annotate(&mut processes, t0 + 3 * minute);
+        assert_eq!(processes[0].idle_secs, Some(0));
+        assert_eq!(processes[1].idle_secs, Some(120));
+    }
+
+    #[test]
+    fn pid_reuse_resets_idle_time() {
+        let t0 = Instant::now();
+        let mut tracker = IdleTracker::new(1.0);
+        tracker.start(t0);
+        tracker.observe(&[process(7, 0.0)], t0 + Duration::from_secs(600));
+
+        let reused = ProcessInfo { start_time: Some(2000), ..process(7, 0.0) };
+        tracker.observe(std::slice::from_ref(&reused), t0 + Duration::from_secs(660));
+        assert_eq!(tracker.idle_for(&reused, t0 + Duration::from_secs(660)), Some(Duration::from_secs(60)));
+    }
+}
@@ -1,4 +1,5 @@
-use std::io::{self, Write};
+use std::io;
+use std::sync::Arc;
 use crossterm::{
     cursor,
     event::{self, Event, KeyCode, KeyEvent, KeyModifiers},
@@ -6,68 +7,41 @@ use crossterm::{
     style::{Color, Print, ResetColor, SetForegroundColor},
     terminal::{self, ClearType},
 };
-use crate::config::Config;
-use crate::release::{boost, BoostResult};
-use crate::{read_mem_stats, MemStats};
-use crate::processes::{get_all_processes, sort_and_take_processes};
-use crate::hotkey::GlobalHotkey;
+use crate::config::{load_config, Config};
+use crate::release::{boost_with_level, killer_boost_with, BoostResult, ReleaseMethod};
+use crate::MemStats;
+use crate::measure::ReclaimMeasurement;
+use crate::stats::{default_provider, provider_from_config, MemoryStatsProvider};
+use crate::strategy::StepStatus;
+use crate::processes::{sort_and_take_processes_by, ProcessMonitor};
 use crate::version::{check_for_updates, perform_update};
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub enum BoostLevel {
-    Low,
-    Medium,
-    High,
-}
-
-impl BoostLevel {
-    pub fn description(&self) -> &'static str {
-        match self {
-            BoostLevel::Low => "轻度清理 - 基础内存优化",
-            BoostLevel::Medium => "中度清理 - 标准内存释放",
-            BoostLevel::High => "强力清理 - 深度内存优化",
-        }
-    }
-
-    pub fn icon(&self) -> &'static str {
-        match self {
-            BoostLevel::Low => "🟡",
-            BoostLevel::Medium => "🟠",
-            BoostLevel::High => "🔴",
-        }
-    }
-
-    pub fn next(&self) -> BoostLevel {
-        match self {
-            BoostLevel::Low => BoostLevel::Medium,
-            BoostLevel::Medium => BoostLevel::High,
-            BoostLevel::High => BoostLevel::Low,
-        }
-    }
-
-    pub fn prev(&self) -> BoostLevel {
-        match self {
-            BoostLevel::High => BoostLevel::Medium,
-            BoostLevel::Medium => BoostLevel::Low,
-            BoostLevel::Low => BoostLevel::High,
-        }
-    }
-}
+pub use crate::strategy::BoostLevel;
 
 pub struct InteractiveTerminal {
     config: Config,
     current_level: BoostLevel,
     running: bool,
     input_buffer: String,
+    provider: Arc<dyn MemoryStatsProvider>,
+    /// 整个会话共用，/status 多次执行时 CPU 使用率基于上一次刷新
+    monitor: ProcessMonitor,
 }
 
 impl InteractiveTerminal {
     pub fn new(config: Config) -> Self {
+        let provider = provider_from_config(&config).unwrap_or_else(|_| default_provider());
+        Self::with_provider(config, provider)
+    }
+
+    pub fn with_provider(config: Config, provider: Arc<dyn MemoryStatsProvider>) -> Self {
         Self {
+            monitor: ProcessMonitor::from_config(&config).unwrap_or_default(),
+            current_level: config.boost.default_level,
             config,
-            current_level: BoostLevel::Medium,
             running: true,
             input_buffer: String::new(),
+            provider,
         }
     }
 
@@ -198,10 +172,10 @@ impl InteractiveTerminal {
         Ok(())
     }
 
-    fn execute_boost(&self) -> Result<(), Box<dyn std::error::Error>> {
+    fn execute_boost(&mut self) -> Result<(), Box<dyn std::error::Error>> {
         println!("🚀 开始执行 {} 内存清理...", self.current_level.description());
 
-        match boost() {
+        match boost_with_level(self.provider.as_ref(), &mut self.monitor, &self.config, self.current_level) {
             Ok(result) => {
                 self.print_boost_result(&result)?;
             }
@@ -262,20 +236,21 @@ impl InteractiveTerminal {
         Ok(())
     }
 
-    fn show_status(&self) -> Result<(), Box<dyn std::error::Error>> {
+    fn show_status(&mut self) -> Result<(), Box<dyn std::error::Error>> {
         println!("📊 系统内存状态:");
 
-        match read_mem_stats() {
+        match self.provider.read_mem_stats() {
             Ok(mem_stats) => {
                 self.print_memory_stats(&mem_stats)?;
 
                 // 显示进程信息
-                let processes = get_all_processes();
-                let top_processes = sort_and_take_processes(processes, 5);
+                let processes = self.monitor.refresh_with_cpu();
+                let metric = self.config.memory_metric;
+                let top_processes = sort_and_take_processes_by(processes, 5, metric);
 
                 println!("\n🔝 内存占用前5的进程:");
-                println!("{:<8} {:<25} {:>12}", "PID", "名称", "内存(MB)");
-                println!("{:-<8} {:-<25} {:->12}", "", "", "");
+                println!("{:<8} {:<25} {:>12} {:>8}", "PID", "名称", format!("{}(MB)", metric.label()), "CPU%");
+                println!("{:-<8} {:-<25} {:->12} {:->8}", "", "", "", "");
 
                 for p in &top_processes {
                     let name = if p.name.len() > 23 {
@@ -283,7 +258,7 @@ impl InteractiveTerminal {
                     } else {
                         p.name.clone()
                     };
-                    println!("{:<8} {:<25} {:>12}", p.pid, name, p.rss_mb);
+                    println!("{:<8} {:<25} {:>12} {:>8.1}", p.pid, name, p.memory_mb(metric), p.cpu_usage);
                 }
             }
             Err(e) => {
@@ -336,6 +311,7 @@ impl InteractiveTerminal {
 
     fn print_boost_result(&self, result: &BoostResult) -> Result<(), Box<dyn std::error::Error>> {
         println!("✅ 内存清理完成!");
+        println!("   方式: {}", describe_steps(result));
         println!("   用时: {:.2}秒", result.duration.as_secs_f32());
 
         if result.delta_mb >= 0 {
@@ -346,12 +322,17 @@ impl InteractiveTerminal {
 
         println!("   清理前: {} MB 可用", result.before.free_mb);
         println!("   清理后: {} MB 可用", result.after.free_mb);
+        if let Some(measurement) = &result.measurement {
+            print_measurement(measurement);
+        }
+        print_step_details(result);
         Ok(())
     }
 
     fn print_memory_stats(&self, stats: &MemStats) -> Result<(), Box<dyn std::error::Error>> {
         println!("   总内存: {} MB", stats.total_mb);
         println!("   可用内存: {} MB", stats.free_mb);
+        println!("   可分配内存: {} MB", stats.available_mb);
         println!("   活跃内存: {} MB", stats.active_mb);
         println!("   非活跃内存: {} MB", stats.inactive_mb);
         println!("   固定内存: {} MB", stats.wired_mb);
@@ -464,12 +445,75 @@ impl InteractiveTerminal {
     }
 }
 
+fn describe_method(method: &ReleaseMethod) -> String {
+    match method {
+        ReleaseMethod::Purge => "purge".to_string(),
+        ReleaseMethod::DropCaches { mode } => format!("drop_caches={}", mode.value()),
+    }
+}
+
+/// 列出成功执行的步骤，缓存释放步骤显示具体方式
+fn describe_steps(result: &BoostResult) -> String {
+    let names: Vec<String> = result.steps.iter()
+        .filter(|step| step.status == StepStatus::Completed)
+        .map(|step| match (&result.method, step.name.as_str()) {
+            (Some(method), "purge" | "drop_caches") => describe_method(method),
+            _ => step.name.clone(),
+        })
+        .collect();
+    names.join(" → ")
+}
+
+/// 打印采样窗口内的回收测量
+fn print_measurement(measurement: &ReclaimMeasurement) {
+    println!("   采样窗口: {:.1}秒 ({} 次采样)", measurement.window.as_secs_f32(), measurement.samples);
+    println!("   空闲内存增量: 峰值 {} MB，最终 {} MB", measurement.peak_free_delta_mb, measurement.final_free_delta_mb);
+    println!("   可分配内存变化: {} MB", measurement.available_delta_mb);
+    let fields = &measurement.fields;
+    println!("   明细: 空闲 {:+} MB / 非活跃 {:+} MB / 压缩 {:+} MB", fields.free_mb, fields.inactive_mb, fields.compressed_mb);
+}
+
+/// 打印跳过/失败的步骤以及建议或终止的进程
+fn print_step_details(result: &BoostResult) {
+    for step in &result.steps {
+        match step.status {
+            StepStatus::Skipped => println!("   跳过 {}: {}", step.name, step.error.as_deref().unwrap_or("")),
+            StepStatus::Failed => println!("   {} 失败: {}", step.name, step.error.as_deref().unwrap_or("")),
+            StepStatus::Completed => {
+                let Some(processes) = step.details.get("processes").and_then(|v| v.as_array()) else {
+                    continue;
+                };
+                if processes.is_empty() {
+                    continue;
+                }
+                let terminating = step.name == "terminate";
+                println!("   {}:", if terminating { "已终止进程" } else { "建议终止的进程" });
+                for p in processes {
+                    let mark = match p.get("terminated").and_then(|v| v.as_bool()) {
+                        Some(true) => "✅ ",
+                        Some(false) => "❌ ",
+                        None => "",
+                    };
+                    println!("     {}{} {} ({} MB)", mark, p["pid"], p["name"].as_str().unwrap_or(""), p["rss_mb"]);
+                }
+            }
+        }
+    }
+}
+
 // 简化模式 - 用于兼容原有的 rb b 命令
 pub fn run_direct_boost() -> Result<(), Box<dyn std::error::Error>> {
-    println!("🚀 执行中等强度内存清理...");
-    match boost() {
+    let config = load_config().unwrap_or_default();
+    run_direct_boost_with(&config, default_provider().as_ref())
+}
+
+pub fn run_direct_boost_with(config: &Config, provider: &dyn MemoryStatsProvider) -> Result<(), Box<dyn std::error::Error>> {
+    let level = config.boost.default_level;
+    println!("🚀 执行{}...", level.description());
+    match boost_with_level(provider, &mut ProcessMonitor::from_config(config).unwrap_or_default(), config, level) {
         Ok(result) => {
             println!("✅ 内存清理完成!");
+            println!("   方式: {}", describe_steps(&result));
             println!("   用时: {:.2}秒", result.duration.as_secs_f32());
             if result.delta_mb >= 0 {
                 println!("   释放内存: {} MB", result.delta_mb);
@@ -478,10 +522,44 @@ pub fn run_direct_boost() -> Result<(), Box<dyn std::error::Error>> {
             }
             println!("   清理前: {} MB 可用", result.before.free_mb);
             println!("   清理后: {} MB 可用", result.after.free_mb);
+            if let Some(measurement) = &result.measurement {
+                print_measurement(measurement);
+            }
+            print_step_details(&result);
         }
         Err(e) => {
             println!("❌ 内存清理失败: {:?}", e);
         }
     }
     Ok(())
-}
\ No newline at end of file
+}
+
+/// rb b：一键执行 Killer 模式
+pub fn run_killer_boost_with(config: &Config, provider: &dyn MemoryStatsProvider) -> Result<(), Box<dyn std::error::Error>> {
+    println!("🔥 执行 Killer 模式 (三轮清理)...");
+    match killer_boost_with(provider, &mut ProcessMonitor::from_config(config).unwrap_or_default(), config) {
+        Ok(report) => {
+            for round in &report.rounds {
+                let step = &round.step;
+                match step.status {
+                    StepStatus::Completed => println!("   第{}轮 {}: 释放 {} MB ({:.2}秒)", round.round, round.title, step.delta_mb, step.duration.as_secs_f32()),
+                    StepStatus::Skipped => println!("   第{}轮 {}: 跳过 ({})", round.round, round.title, step.error.as_deref().unwrap_or("")),
+                    StepStatus::Failed => println!("   第{}轮 {}: 失败 ({})", round.round, round.title, step.error.as_deref().unwrap_or("")),
+                }
+            }
+            if !report.termination_enabled {
+                println!("   💡 进程终止未开启，可在配置中设置 enable_process_termination = true");
+            }
+            println!("✅ Killer 模式完成! 共释放 {} MB，用时 {:.2}秒", report.delta_mb, report.duration.as_secs_f32());
+            println!("   清理前: {} MB 可用", report.before.free_mb);
+            println!("   清理后: {} MB 可用", report.after.free_mb);
+            if let Some(measurement) = &report.measurement {
+                print_measurement(measurement);
+            }
+        }
+        Err(e) => {
+            println!("❌ Killer 模式失败: {:?}", e);
+        }
+    }
+    Ok(())
+}
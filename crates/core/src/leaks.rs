@@ -0,0 +1,198 @@
+use std::collections::{HashMap, VecDeque};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use crate::config::LeakConfig;
+use crate::processes::ProcessInfo;
+
+/// 某一时刻的常驻内存
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct LeakSample {
+    pub at: DateTime<Utc>,
+    pub rss_mb: u64,
+}
+
+/// 疑似泄漏的进程
+#[derive(Debug, Clone, Serialize)]
+pub struct LeakSuspect {
+    pub pid: u32,
+    pub name: String,
+    pub cmd: Vec<String>,
+    /// 最小二乘拟合的增长速度
+    pub slope_mb_per_hour: f64,
+    /// 第一次到最后一次采样的变化
+    pub growth_mb: i64,
+    /// 相邻采样中不下降的比例
+    pub monotonic_ratio: f64,
+    pub samples: Vec<LeakSample>,
+}
+
+struct History {
+    name: String,
+    cmd: Vec<String>,
+    samples: VecDeque<LeakSample>,
+}
+
+/// 基于周期性进程快照的内存增长检测：对每个进程的 RSS 做线性拟合，
+/// 持续增长且斜率超过阈值的进程视为疑似泄漏。以 (pid, 启动时间) 为键，PID 复用时重新记录
+pub struct LeakDetector {
+    config: LeakConfig,
+    histories: HashMap<(u32, Option<u64>), History>,
+    last_sample: Option<DateTime<Utc>>,
+}
+
+impl LeakDetector {
+    pub fn new(config: LeakConfig) -> Self {
+        Self {
+            config,
+            histories: HashMap::new(),
+            last_sample: None,
+        }
+    }
+
+    pub fn config(&self) -> &LeakConfig {
+        &self.config
+    }
+
+    pub fn observe(&mut self, processes: &[ProcessInfo]) {
+        self.observe_at(processes, Utc::now());
+    }
+
+    /// 记录一次快照；距上次记录不足 sample_interval_secs 的快照会被忽略，已退出进程的历史被丢弃
+    pub fn observe_at(&mut self, processes: &[ProcessInfo], at: DateTime<Utc>) {
+        if let Some(last) = self.last_sample {
+            if (at - last).num_seconds() < self.config.sample_interval_secs as i64 {
+                return;
+            }
+        }
+        self.last_sample = Some(at);
+
+        let max_samples = self.config.max_samples.max(2);
+        let mut histories = HashMap::with_capacity(processes.len());
+        for p in processes {
+            let key = (p.pid, p.start_time);
+            let mut history = self.histories.remove(&key).unwrap_or_else(|| History {
+                name: p.name.clone(),
+                cmd: p.cmd.clone(),
+                samples: VecDeque::new(),
+            });
+            if history.samples.len() >= max_samples {
+                history.samples.pop_front();
+            }
+            history.samples.push_back(LeakSample { at, rss_mb: p.rss_mb });
+            histories.insert(key, history);
+        }
+        self.histories = histories;
+    }
+
+    /// 当前的疑似泄漏进程，按增长速度降序
+    pub fn suspects(&self) -> Vec<LeakSuspect> {
+        let mut suspects: Vec<LeakSuspect> = self.histories.iter()
+            .filter(|(_, history)| history.samples.len() >= self.config.min_samples.max(2))
+            .filter_map(|(&(pid, _), history)| {
+                let samples: Vec<LeakSample> = history.samples.iter().copied().collect();
+                let slope_mb_per_hour = fit_slope_mb_per_hour(&samples)?;
+                let monotonic_ratio = monotonic_ratio(&samples);
+                let growth_mb = samples[samples.len() - 1].rss_mb as i64 - samples[0].rss_mb as i64;
+
+                let leaking = growth_mb > 0
+                    && slope_mb_per_hour >= self.config.min_slope_mb_per_hour
+                    && monotonic_ratio >= self.config.min_monotonic_ratio;
+                leaking.then(|| LeakSuspect {
+                    pid,
+                    name: history.name.clone(),
+                    cmd: history.cmd.clone(),
+                    slope_mb_per_hour,
+                    growth_mb,
+                    monotonic_ratio,
+                    samples,
+                })
+            })
+            .collect();
+        suspects.sort_by(|a, b| b.slope_mb_per_hour.total_cmp(&a.slope_mb_per_hour));
+        suspects
+    }
+}
+
+/// RSS 对时间的最小二乘斜率（MB/小时）；采样时间全部相同时无法拟合
+fn fit_slope_mb_per_hour(samples: &[LeakSample]) -> Option<f64> {
+    let start = samples.first()?.at;
+    let points: Vec<(f64, f64)> = samples.iter()
+        .map(|s| ((s.at - start).num_milliseconds() as f64 / 3_600_000.0, s.rss_mb as f64))
+        .collect();
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|p| p.0).sum::<f64>() / n;
+    let mean_y = points.iter().map(|p| p.1).sum::<f64>() / n;
+    let var_x: f64 = points.iter().map(|p| (p.0 - mean_x).powi(2)).sum();
+    if var_x <= f64::EPSILON {
+        return None;
+    }
+    let cov: f64 = points.iter().map(|p| (p.0 - mean_x) * (p.1 - mean_y)).sum();
+    Some(cov / var_x)
+}
+
+fn monotonic_ratio(samples: &[LeakSample]) -> f64 {
+    if samples.len() < 2 {
+        return 0.0;
+    }
+    let rising = samples.windows(2).filter(|w| w[1].rss_mb >= w[0].rss_mb).count();
+    rising as f64 / (samples.len() - 1) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn process(pid: u32, name: &str, rss_mb: u64) -> ProcessInfo {
+        ProcessInfo { pid, name: name.to_string(), rss_mb, start_time: Some(100), ..Default::default() }
+    }
+
+    fn config() -> LeakConfig {
+        LeakConfig { sample_interval_secs: 60, min_samples: 5, ..LeakConfig::default() }
+    }
+
+    #[test]
+    fn flags_steady_growth_above_slope() {
+        let mut detector = LeakDetector::new(config());
+        let t0 = Utc::now();
+        // 每 10 分钟：language server 增长 50 MB（300 MB/小时），编辑器上下波动，构建进程增长很慢
+        let jitter = [0i64, 30, -20, 10, -30, 20];
+        for i in 0..6u64 {
+            detector.observe_at(&[
+                process(100, "rust-analyzer", 400 + 50 * i),
+                process(200, "editor", (800 + jitter[i as usize]) as u64),
+                process(300, "builder", 1000 + i),
+            ], t0 + Duration::minutes(10 * i as i64));
+        }
+
+        let suspects = detector.suspects();
+        assert_eq!(suspects.len(), 1);
+        let suspect = &suspects[0];
+        assert_eq!(suspect.pid, 100);
+        assert!((suspect.slope_mb_per_hour - 300.0).abs() < 0.01);
+        assert_eq!(suspect.growth_mb, 250);
+        assert_eq!(suspect.monotonic_ratio, 1.0);
+        assert_eq!(suspect.samples.len(), 6);
+    }
+
+    #[test]
+    fn needs_enough_samples_and_spacing() {
+        let mut detector = LeakDetector::new(config());
+        let t0 = Utc::now();
+        for i in 0..4 {
+            detector.observe_at(&[process(100, "node", 100 + 100 * i)], t0 + Duration::minutes(i as i64));
+        }
+        assert!(detector.suspects().is_empty());
+
+        // 间隔太短的快照被忽略
+        detector.observe_at(&[process(100, "node", 5000)], t0 + Duration::minutes(3) + Duration::seconds(10));
+        assert!(detector.suspects().is_empty());
+
+        detector.observe_at(&[process(100, "node", 500)], t0 + Duration::minutes(4));
+        assert_eq!(detector.suspects().len(), 1);
+
+        // 进程退出后历史被丢弃
+        detector.observe_at(&[], t0 + Duration::minutes(5));
+        assert!(detector.suspects().is_empty());
+    }
+}
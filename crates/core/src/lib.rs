@@ -8,14 +8,28 @@ pub mod security;
 pub mod interactive;
 pub mod hotkey;
 pub mod version;
+pub mod stats;
+pub mod psi;
+pub mod strategy;
+pub mod measure;
+pub mod procfs;
+pub mod tree;
+pub mod idle;
+pub mod leaks;
+pub mod foreground;
+pub mod cgroup;
+pub mod termination;
+pub mod budget;
+pub mod relaunch;
+pub mod rules;
 
 use serde::{Serialize, Deserialize};
-use std::mem;
+use psi::{derive_pressure_from_psi, PsiStats, PsiThresholds};
 
 // Define constants for memory conversion
-const BYTES_PER_MB: u64 = 1024 * 1024;
+pub(crate) const BYTES_PER_MB: u64 = 1024 * 1024;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct MemStats {
     pub total_mb: u64,
     pub free_mb: u64,
@@ -23,16 +37,39 @@ pub struct MemStats {
     pub inactive_mb: u64, // Not available in sysinfo
     pub wired_mb: u64, // Not available in sysinfo
     pub compressed_mb: u64, // Not available in sysinfo
+    /// 可立即分配的内存（Linux 的 MemAvailable；mach 上为 free + inactive）
+    #[serde(default)]
+    pub available_mb: u64,
+    /// Linux PSI 停顿数据（/proc/pressure/memory），其他平台为 None
+    #[serde(default)]
+    pub psi: Option<PsiStats>,
+    /// 交换区用量和换入/换出速率
+    #[serde(default)]
+    pub swap: SwapStats,
     pub pressure: PressureLevel,
 }
 
-#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
-pub enum PressureLevel { Normal, Warning, Critical }
+/// 交换区统计；速率由相邻两次读取的累计计数推算
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SwapStats {
+    pub total_mb: u64,
+    pub used_mb: u64,
+    /// 每秒换入的页数；首次读取或平台不提供计数时为 None
+    pub swap_in_per_sec: Option<f64>,
+    /// 每秒换出的页数
+    pub swap_out_per_sec: Option<f64>,
+}
+
+#[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize)]
+pub enum PressureLevel { #[default] Normal, Warning, Critical }
 
-#[cfg(not(feature = "use-sysinfo"))]
-fn derive_pressure_level(stats: &MemStats) -> PressureLevel {
+pub(crate) fn derive_pressure_level(stats: &MemStats) -> PressureLevel {
     if stats.total_mb == 0 { return PressureLevel::Normal; }
-    let available_mb = stats.free_mb + stats.inactive_mb;
+    let available_mb = if stats.available_mb > 0 {
+        stats.available_mb
+    } else {
+        stats.free_mb + stats.inactive_mb
+    };
     let available_ratio = available_mb as f64 / stats.total_mb as f64;
     let compressed_ratio = stats.compressed_mb as f64 / stats.total_mb as f64;
 
@@ -45,84 +82,17 @@ fn derive_pressure_level(stats: &MemStats) -> PressureLevel {
     }
 }
 
-#[cfg(feature = "use-sysinfo")]
-fn derive_pressure_level(stats: &MemStats) -> PressureLevel {
-    if stats.total_mb == 0 { return PressureLevel::Normal; }
-    let free_ratio = stats.free_mb as f64 / stats.total_mb as f64;
-
-    if free_ratio < 0.05 {
-        PressureLevel::Critical
-    } else if free_ratio < 0.15 {
-        PressureLevel::Warning
-    } else {
-        PressureLevel::Normal
+/// 给定 PSI 阈值且 stats 带有 PSI 数据时按停顿时间推导压力等级，否则回退到比例启发式
+pub fn derive_pressure(stats: &MemStats, psi_thresholds: Option<&PsiThresholds>) -> PressureLevel {
+    match (&stats.psi, psi_thresholds) {
+        (Some(psi), Some(thresholds)) => derive_pressure_from_psi(psi, thresholds),
+        _ => derive_pressure_level(stats),
     }
 }
 
-#[cfg(not(feature = "use-sysinfo"))]
+/// 使用默认后端读取内存统计，需要指定后端时见 [`stats::MemoryStatsProvider`]
 pub fn read_mem_stats() -> Result<MemStats, String> {
-    unsafe {
-        #[allow(deprecated)]
-        let host_port = libc::mach_host_self();
-        if host_port == 0 { return Err("mach_host_self() returned 0".to_string()); }
-
-        let mut vm_stats: libc::vm_statistics64 = mem::zeroed();
-        let mut count = libc::HOST_VM_INFO64_COUNT;
-
-        let kern_return = libc::host_statistics64(
-            host_port,
-            libc::HOST_VM_INFO64,
-            &mut vm_stats as *mut _ as libc::host_info64_t,
-            &mut count,
-        );
-
-        if kern_return != libc::KERN_SUCCESS { return Err(format!("host_statistics64() failed with code {}", kern_return)); }
-
-        let page_size = libc::sysconf(libc::_SC_PAGESIZE) as u64;
-        if page_size == 0 { return Err("sysconf(_SC_PAGESIZE) returned 0".to_string()); }
-
-        let to_mb = |pages: u32| (pages as u64 * page_size) / BYTES_PER_MB;
-
-        let mut total_mem: u64 = 0;
-        let mut mib: [i32; 2] = [libc::CTL_HW, libc::HW_MEMSIZE];
-        let mut size = mem::size_of::<u64>();
-        if libc::sysctl(mib.as_mut_ptr(), 2, &mut total_mem as *mut _ as *mut libc::c_void, &mut size, std::ptr::null_mut(), 0) != 0 {
-            return Err("sysctl for HW_MEMSIZE failed".to_string());
-        }
-
-        let mut stats = MemStats {
-            total_mb: total_mem / BYTES_PER_MB,
-            free_mb: to_mb(vm_stats.free_count),
-            active_mb: to_mb(vm_stats.active_count),
-            inactive_mb: to_mb(vm_stats.inactive_count),
-            wired_mb: to_mb(vm_stats.wire_count),
-            compressed_mb: to_mb(vm_stats.compressor_page_count),
-            pressure: PressureLevel::Normal,
-        };
-
-        stats.pressure = derive_pressure_level(&stats);
-        Ok(stats)
-    }
-}
-
-#[cfg(feature = "use-sysinfo")]
-pub fn read_mem_stats() -> Result<MemStats, String> {
-    use sysinfo::{System};
-    let mut sys = System::new_all();
-    sys.refresh_memory();
-
-    let mut stats = MemStats {
-        total_mb: sys.total_memory() / BYTES_PER_MB,
-        free_mb: sys.free_memory() / BYTES_PER_MB,
-        active_mb: 0,
-        inactive_mb: 0,
-        wired_mb: 0,
-        compressed_mb: 0,
-        pressure: PressureLevel::Normal,
-    };
-
-    stats.pressure = derive_pressure_level(&stats);
-    Ok(stats)
+    stats::default_provider().read_mem_stats()
 }
 
 #[cfg(test)]
@@ -147,6 +117,9 @@ mod tests {
             inactive_mb: 0,
             wired_mb: 0,
             compressed_mb: 0,
+            available_mb: 0,
+            psi: None,
+            swap: Default::default(),
             pressure: PressureLevel::Normal,
         };
 
@@ -167,5 +140,28 @@ mod tests {
         stats.inactive_mb = 100;
         stats.compressed_mb = 1000;
         assert_eq!(derive_pressure_level(&stats), PressureLevel::Critical);
+
+        // MemAvailable takes precedence over free + inactive
+        stats.available_mb = 4000;
+        assert_eq!(derive_pressure_level(&stats), PressureLevel::Normal);
+    }
+
+    #[test]
+    fn psi_pressure_overrides_ratios_when_enabled() {
+        let mut stats = MemStats {
+            total_mb: 16384,
+            free_mb: 8000,
+            available_mb: 10000,
+            ..Default::default()
+        };
+        let thresholds = PsiThresholds::default();
+
+        // No PSI data: fall back to ratio heuristics
+        assert_eq!(derive_pressure(&stats, Some(&thresholds)), PressureLevel::Normal);
+
+        stats.psi = Some(psi::parse_psi_str("some avg10=55.00 avg60=20.00 avg300=5.00 total=1000\n").unwrap());
+        assert_eq!(derive_pressure(&stats, Some(&thresholds)), PressureLevel::Critical);
+        // PSI present but not enabled
+        assert_eq!(derive_pressure(&stats, None), PressureLevel::Normal);
     }
 }
@@ -0,0 +1,146 @@
+use std::thread;
+use std::time::Duration;
+use serde::Serialize;
+use crate::config::ReleaseConfig;
+use crate::stats::MemoryStatsProvider;
+use crate::MemStats;
+
+/// 清理后的采样窗口：在 window 内等间隔读取 samples 次内存统计
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SettleWindow {
+    pub window: Duration,
+    pub samples: u32,
+}
+
+impl SettleWindow {
+    /// 窗口或采样次数为 0 时不做采样，沿用清理后立即读取的结果
+    pub fn from_config(config: &ReleaseConfig) -> Option<Self> {
+        if config.settle_window_ms == 0 || config.settle_samples == 0 {
+            return None;
+        }
+        Some(Self {
+            window: Duration::from_millis(config.settle_window_ms),
+            samples: config.settle_samples,
+        })
+    }
+}
+
+/// 各字段相对清理前的变化（MB，正数表示增加）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct FieldDeltas {
+    pub free_mb: i64,
+    pub available_mb: i64,
+    pub inactive_mb: i64,
+    pub compressed_mb: i64,
+    pub active_mb: i64,
+    pub wired_mb: i64,
+}
+
+impl FieldDeltas {
+    pub fn between(before: &MemStats, after: &MemStats) -> Self {
+        let delta = |a: u64, b: u64| b as i64 - a as i64;
+        Self {
+            free_mb: delta(before.free_mb, after.free_mb),
+            available_mb: delta(before.available_mb, after.available_mb),
+            inactive_mb: delta(before.inactive_mb, after.inactive_mb),
+            compressed_mb: delta(before.compressed_mb, after.compressed_mb),
+            active_mb: delta(before.active_mb, after.active_mb),
+            wired_mb: delta(before.wired_mb, after.wired_mb),
+        }
+    }
+}
+
+/// 采样窗口内的回收量
+#[derive(Debug, Clone, Serialize)]
+pub struct ReclaimMeasurement {
+    #[serde(with = "humantime_serde")]
+    pub window: Duration,
+    pub samples: u32,
+    /// 窗口内空闲内存相对清理前的最大增量
+    pub peak_free_delta_mb: i64,
+    /// 窗口结束时的空闲内存增量
+    pub final_free_delta_mb: i64,
+    /// 窗口结束时的可分配内存增量
+    pub available_delta_mb: i64,
+    /// 窗口结束时各字段的变化
+    pub fields: FieldDeltas,
+}
+
+/// 在窗口内多次采样，返回测量结果和最后一次采样
+pub fn measure_reclaim(
+    provider: &dyn MemoryStatsProvider,
+    before: &MemStats,
+    settle: SettleWindow,
+) -> Result<(ReclaimMeasurement, MemStats), String> {
+    let interval = settle.window / settle.samples;
+    let mut peak_free_mb = before.free_mb;
+    let mut last = before.clone();
+
+    for _ in 0..settle.samples {
+        thread::sleep(interval);
+        last = provider.read_mem_stats()?;
+        peak_free_mb = peak_free_mb.max(last.free_mb);
+    }
+
+    let fields = FieldDeltas::between(before, &last);
+    let measurement = ReclaimMeasurement {
+        window: settle.window,
+        samples: settle.samples,
+        peak_free_delta_mb: peak_free_mb as i64 - before.free_mb as i64,
+        final_free_delta_mb: fields.free_mb,
+        available_delta_mb: fields.available_mb,
+        fields,
+    };
+    Ok((measurement, last))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::FakeStatsProvider;
+
+    fn stats(free_mb: u64, inactive_mb: u64) -> MemStats {
+        MemStats {
+            total_mb: 16384,
+            free_mb,
+            inactive_mb,
+            available_mb: free_mb + inactive_mb,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn reports_peak_final_and_field_deltas() {
+        let before = stats(1000, 3000);
+        let provider = FakeStatsProvider::with_samples(vec![stats(2500, 1500), stats(2200, 1600), stats(2000, 1700)]);
+        let settle = SettleWindow { window: Duration::from_millis(30), samples: 3 };
+
+        let (measurement, last) = measure_reclaim(&provider, &before, settle).unwrap();
+        assert_eq!(measurement.peak_free_delta_mb, 1500);
+        assert_eq!(measurement.final_free_delta_mb, 1000);
+        assert_eq!(measurement.available_delta_mb, -300);
+        assert_eq!(measurement.fields.inactive_mb, -1300);
+        assert_eq!(last.free_mb, 2000);
+    }
+
+    #[test]
+    fn peak_never_drops_below_zero() {
+        let before = stats(1000, 0);
+        let provider = FakeStatsProvider::with_samples(vec![stats(900, 0), stats(800, 0)]);
+        let settle = SettleWindow { window: Duration::from_millis(10), samples: 2 };
+
+        let (measurement, _) = measure_reclaim(&provider, &before, settle).unwrap();
+        assert_eq!(measurement.peak_free_delta_mb, 0);
+        assert_eq!(measurement.final_free_delta_mb, -200);
+    }
+
+    #[test]
+    fn zero_window_disables_sampling() {
+        let mut config = ReleaseConfig::default();
+        assert!(SettleWindow::from_config(&config).is_none());
+        config.settle_window_ms = 2000;
+        assert!(SettleWindow::from_config(&config).is_some());
+        config.settle_samples = 0;
+        assert!(SettleWindow::from_config(&config).is_none());
+    }
+}
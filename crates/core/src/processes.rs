@@ -1,10 +1,21 @@
-use sysinfo::System;
-use objc2_app_kit::NSWorkspace;
-use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::ffi::CStr;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use sysinfo::{ProcessRefreshKind, ProcessStatus, ProcessesToUpdate, System, UpdateKind};
+use serde::{Deserialize, Serialize};
+use crate::cgroup::{read_process_cgroup, CgroupInfo};
+use crate::config::Config;
+use crate::foreground::{default_detector, ForegroundDetector};
+use crate::idle::IdleTracker;
+use crate::procfs::ProcfsCollector;
+use crate::tree::ProcessGrouping;
 
 const BYTES_PER_MB: u64 = 1024 * 1024;
+/// 未从配置读取时的空闲 CPU 阈值（百分比）
+const DEFAULT_IDLE_CPU_THRESHOLD: f32 = 1.0;
 
-#[derive(Debug, Clone, PartialEq, Serialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct ProcessInfo {
     pub pid: u32,
     pub name: String,
@@ -12,37 +23,449 @@ pub struct ProcessInfo {
     pub rss_mb: u64,
     pub cpu_usage: f32,
     pub is_frontmost: bool,
+    #[serde(default)]
+    pub ppid: Option<u32>,
+    #[serde(default)]
+    pub uid: Option<u32>,
+    #[serde(default)]
+    pub user: Option<String>,
+    /// 启动时间（Unix 时间戳，秒）
+    #[serde(default)]
+    pub start_time: Option<u64>,
+    #[serde(default)]
+    pub state: ProcessState,
+    #[serde(default)]
+    pub threads: Option<u32>,
+    #[serde(default)]
+    pub virtual_mb: u64,
+    #[serde(default)]
+    pub exe: Option<PathBuf>,
+    /// 被换出的大小，平台不提供时为 None
+    #[serde(default)]
+    pub swap_mb: Option<u64>,
+    /// 按共享进程数均摊共享页后的占用（smaps_rollup）
+    #[serde(default)]
+    pub pss_mb: Option<u64>,
+    /// 仅该进程独占的页，大致等于终止后能释放的量（smaps_rollup）
+    #[serde(default)]
+    pub uss_mb: Option<u64>,
+    /// CPU 使用率持续低于空闲阈值的秒数；只有长期持有的 [`ProcessMonitor`] 才会填写
+    #[serde(default)]
+    pub idle_secs: Option<u64>,
+    /// cgroup v2 路径及推断出的容器/unit（仅 Linux）
+    #[serde(default)]
+    pub cgroup: Option<CgroupInfo>,
 }
 
-fn get_frontmost_pid() -> Option<u32> {
-    unsafe {
-        let workspace = NSWorkspace::sharedWorkspace();
-        let front_app = workspace.frontmostApplication()?;
-        Some(front_app.processIdentifier() as u32)
+impl ProcessInfo {
+    /// 按指定口径的内存占用；PSS/USS 不可用时退回 RSS
+    pub fn memory_mb(&self, metric: MemoryMetric) -> u64 {
+        match metric {
+            MemoryMetric::Rss => self.rss_mb,
+            MemoryMetric::Pss => self.pss_mb.unwrap_or(self.rss_mb),
+            MemoryMetric::Uss => self.uss_mb.unwrap_or(self.rss_mb),
+        }
     }
 }
 
-pub fn get_all_processes() -> Vec<ProcessInfo> {
-    let mut sys = System::new_all();
-    sys.refresh_all();
+/// 进程内存口径
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MemoryMetric {
+    #[default]
+    Rss,
+    Pss,
+    Uss,
+}
+
+impl MemoryMetric {
+    /// 是否需要读取 smaps_rollup
+    pub fn needs_smaps(self) -> bool {
+        self != MemoryMetric::Rss
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            MemoryMetric::Rss => "RSS",
+            MemoryMetric::Pss => "PSS",
+            MemoryMetric::Uss => "USS",
+        }
+    }
+}
+
+impl std::fmt::Display for MemoryMetric {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MemoryMetric::Rss => write!(f, "rss"),
+            MemoryMetric::Pss => write!(f, "pss"),
+            MemoryMetric::Uss => write!(f, "uss"),
+        }
+    }
+}
+
+impl std::str::FromStr for MemoryMetric {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "rss" => Ok(MemoryMetric::Rss),
+            "pss" => Ok(MemoryMetric::Pss),
+            "uss" => Ok(MemoryMetric::Uss),
+            other => Err(format!("Invalid memory metric '{}', expected rss, pss or uss", other)),
+        }
+    }
+}
+
+/// 进程运行状态
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProcessState {
+    Running,
+    Sleeping,
+    /// 不可中断的睡眠（通常在等待 I/O）
+    DiskSleep,
+    Stopped,
+    Zombie,
+    Idle,
+    Dead,
+    #[default]
+    Unknown,
+}
+
+impl ProcessState {
+    /// `/proc/<pid>/stat` 中的状态字符
+    pub fn from_proc_char(state: char) -> Self {
+        match state {
+            'R' => ProcessState::Running,
+            'S' => ProcessState::Sleeping,
+            'D' => ProcessState::DiskSleep,
+            'T' | 't' => ProcessState::Stopped,
+            'Z' => ProcessState::Zombie,
+            'I' => ProcessState::Idle,
+            'X' | 'x' => ProcessState::Dead,
+            _ => ProcessState::Unknown,
+        }
+    }
+}
+
+impl From<ProcessStatus> for ProcessState {
+    fn from(status: ProcessStatus) -> Self {
+        match status {
+            ProcessStatus::Run => ProcessState::Running,
+            ProcessStatus::Sleep => ProcessState::Sleeping,
+            ProcessStatus::UninterruptibleDiskSleep => ProcessState::DiskSleep,
+            ProcessStatus::Stop | ProcessStatus::Tracing => ProcessState::Stopped,
+            ProcessStatus::Zombie => ProcessState::Zombie,
+            ProcessStatus::Idle => ProcessState::Idle,
+            ProcessStatus::Dead => ProcessState::Dead,
+            _ => ProcessState::Unknown,
+        }
+    }
+}
+
+/// 通过 getpwuid_r 查找用户名
+pub fn lookup_user_name(uid: u32) -> Option<String> {
+    let mut buf = vec![0 as libc::c_char; 4096];
+    let mut pwd: libc::passwd = unsafe { std::mem::zeroed() };
+    let mut result: *mut libc::passwd = std::ptr::null_mut();
+    let rc = unsafe { libc::getpwuid_r(uid, &mut pwd, buf.as_mut_ptr(), buf.len(), &mut result) };
+    if rc != 0 || result.is_null() || pwd.pw_name.is_null() {
+        return None;
+    }
+    Some(unsafe { CStr::from_ptr(pwd.pw_name) }.to_string_lossy().into_owned())
+}
+
+/// uid -> 用户名缓存，避免每次刷新都查询用户数据库
+#[derive(Debug, Default)]
+pub(crate) struct UserNames {
+    cache: HashMap<u32, Option<String>>,
+}
+
+impl UserNames {
+    pub(crate) fn get(&mut self, uid: u32) -> Option<String> {
+        self.cache.entry(uid).or_insert_with(|| lookup_user_name(uid)).clone()
+    }
+}
+
+/// 进程采集后端
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProcessBackend {
+    Sysinfo,
+    /// 直接读取 procfs，参数为挂载点（通常是 /proc）
+    Procfs(PathBuf),
+}
+
+impl ProcessBackend {
+    /// Linux 默认直接读取 /proc，其他平台（或开启 use-sysinfo 时）使用 sysinfo
+    pub fn default_for_platform() -> Self {
+        if cfg!(all(target_os = "linux", not(feature = "use-sysinfo"))) {
+            ProcessBackend::Procfs(PathBuf::from("/proc"))
+        } else {
+            ProcessBackend::Sysinfo
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ProcessBackend::Sysinfo => "sysinfo",
+            ProcessBackend::Procfs(_) => "procfs",
+        }
+    }
+}
+
+/// 按名称选择进程后端（"auto"、"sysinfo"、"procfs"）
+pub fn process_backend_by_name(name: &str, procfs_root: &Path) -> Result<ProcessBackend, String> {
+    match name {
+        "auto" | "" => Ok(match ProcessBackend::default_for_platform() {
+            ProcessBackend::Procfs(_) => ProcessBackend::Procfs(procfs_root.to_path_buf()),
+            backend => backend,
+        }),
+        "sysinfo" => Ok(ProcessBackend::Sysinfo),
+        #[cfg(target_os = "linux")]
+        "procfs" => Ok(ProcessBackend::Procfs(procfs_root.to_path_buf())),
+        other => Err(format!("Unknown or unsupported process backend: {}", other)),
+    }
+}
+
+enum Collector {
+    Sysinfo(System, UserNames),
+    Procfs(ProcfsCollector),
+}
+
+impl Collector {
+    fn new(backend: ProcessBackend) -> Self {
+        match backend {
+            ProcessBackend::Sysinfo => Collector::Sysinfo(System::new(), UserNames::default()),
+            ProcessBackend::Procfs(root) => Collector::Procfs(ProcfsCollector::new(root)),
+        }
+    }
+}
+
+/// 长期持有的进程采样器：保留采集状态，每次只增量刷新进程内存和 CPU，
+/// 两次刷新之间的 CPU 使用率才有意义
+pub struct ProcessMonitor {
+    collector: Collector,
+    refreshes: u32,
+    idle: IdleTracker,
+    foreground: Box<dyn ForegroundDetector>,
+    /// 上次刷新时的前台进程
+    frontmost: HashSet<u32>,
+    /// 读取 cgroup 信息的 procfs 挂载点
+    procfs_root: PathBuf,
+    /// 是否读取 cgroup；默认关闭，避免每次刷新为每个进程多打开一个文件
+    read_cgroups: bool,
+    /// 上次刷新时各进程的 cgroup
+    cgroups: HashMap<u32, CgroupInfo>,
+}
+
+impl Default for ProcessMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProcessMonitor {
+    pub fn new() -> Self {
+        Self::with_backend(ProcessBackend::default_for_platform())
+    }
+
+    pub fn with_backend(backend: ProcessBackend) -> Self {
+        let procfs_root = match &backend {
+            ProcessBackend::Procfs(root) => root.clone(),
+            ProcessBackend::Sysinfo => PathBuf::from("/proc"),
+        };
+        Self {
+            collector: Collector::new(backend),
+            refreshes: 0,
+            idle: IdleTracker::new(DEFAULT_IDLE_CPU_THRESHOLD),
+            foreground: default_detector(&procfs_root),
+            frontmost: HashSet::new(),
+            procfs_root,
+            read_cgroups: false,
+            cgroups: HashMap::new(),
+        }
+    }
+
+    /// 按配置中的 process_backend 创建，procfs 使用 release.procfs_root
+    pub fn from_config(config: &Config) -> Result<Self, String> {
+        let backend = process_backend_by_name(&config.process_backend, &config.release.procfs_root)?;
+        let mut monitor = Self::with_backend(backend);
+        monitor.set_memory_metric(config.memory_metric);
+        monitor.set_idle_threshold(config.idle.cpu_threshold);
+        monitor.set_foreground_detector(default_detector(&config.release.procfs_root));
+        monitor.procfs_root = config.release.procfs_root.clone();
+        monitor.set_read_cgroups(config.group_by == ProcessGrouping::Cgroup);
+        Ok(monitor)
+    }
+
+    /// PSS/USS 口径需要额外读取 smaps_rollup（开销较大），只有 procfs 后端支持
+    pub fn set_memory_metric(&mut self, metric: MemoryMetric) {
+        if let Collector::Procfs(collector) = &mut self.collector {
+            collector.set_read_smaps(metric.needs_smaps());
+        }
+    }
+
+    /// 刷新时读取各进程的 cgroup（仅 Linux），按 cgroup 聚合前需要开启
+    pub fn set_read_cgroups(&mut self, read_cgroups: bool) {
+        self.read_cgroups = read_cgroups;
+        if !read_cgroups {
+            self.cgroups.clear();
+        }
+    }
+
+    /// CPU 使用率低于该百分比的进程计为空闲
+    pub fn set_idle_threshold(&mut self, cpu_threshold: f32) {
+        self.idle.set_cpu_threshold(cpu_threshold);
+    }
 
-    let frontmost_pid = get_frontmost_pid();
+    /// 替换前台进程检测实现，下次刷新时生效
+    pub fn set_foreground_detector(&mut self, detector: Box<dyn ForegroundDetector>) {
+        self.foreground = detector;
+    }
+
+    pub fn foreground_name(&self) -> &'static str {
+        self.foreground.name()
+    }
+
+    pub fn backend_name(&self) -> &'static str {
+        match self.collector {
+            Collector::Sysinfo(..) => "sysinfo",
+            Collector::Procfs(_) => "procfs",
+        }
+    }
+
+    /// 刷新进程表和系统内存并返回快照；已退出的进程会被移除。
+    /// 从第二次刷新起 CPU 使用率有效，同时更新空闲时长。procfs 不可读时回退到 sysinfo
+    pub fn refresh(&mut self) -> Vec<ProcessInfo> {
+        self.refreshes = self.refreshes.saturating_add(1);
+        self.collect();
+        let mut processes = self.snapshot();
+        let terminals = match &self.collector {
+            Collector::Procfs(collector) => Some(collector.terminals()),
+            Collector::Sysinfo(..) => None,
+        };
+        self.frontmost = self.foreground.frontmost_pids(&processes, terminals);
+        if cfg!(target_os = "linux") && self.read_cgroups {
+            self.cgroups = processes.iter()
+                .filter_map(|p| Some((p.pid, read_process_cgroup(&self.procfs_root, p.pid)?)))
+                .collect();
+        }
+        self.label(&mut processes);
+
+        let now = Instant::now();
+        if self.cpu_ready() {
+            self.idle.observe(&processes, now);
+        } else {
+            self.idle.start(now);
+        }
+        self.idle.annotate(&mut processes, now);
+        processes
+    }
+
+    fn collect(&mut self) {
+        match &mut self.collector {
+            Collector::Sysinfo(sys, users) => {
+                let kind = ProcessRefreshKind::nothing()
+                    .with_memory()
+                    .with_cpu()
+                    .with_tasks()
+                    .with_cmd(UpdateKind::OnlyIfNotSet)
+                    .with_exe(UpdateKind::OnlyIfNotSet)
+                    .with_user(UpdateKind::OnlyIfNotSet);
+                sys.refresh_processes_specifics(ProcessesToUpdate::All, true, kind);
+                sys.refresh_memory();
+                for uid in sys.processes().values().filter_map(|p| p.user_id()) {
+                    users.get(**uid);
+                }
+            }
+            Collector::Procfs(collector) => {
+                if let Err(e) = collector.collect() {
+                    eprintln!("警告: 读取 {} 失败 ({})，改用 sysinfo 进程后端", collector.root().display(), e);
+                    self.collector = Collector::Sysinfo(System::new(), UserNames::default());
+                    self.refreshes = 1;
+                    self.collect();
+                }
+            }
+        }
+    }
+
+    /// 上次刷新时的快照（含空闲时长），不触发刷新
+    pub fn processes(&self) -> Vec<ProcessInfo> {
+        let mut processes = self.snapshot();
+        self.label(&mut processes);
+        self.idle.annotate(&mut processes, Instant::now());
+        processes
+    }
+
+    /// 填入上次刷新得到的前台标记和 cgroup
+    fn label(&self, processes: &mut [ProcessInfo]) {
+        for p in processes.iter_mut() {
+            p.is_frontmost = self.frontmost.contains(&p.pid);
+            p.cgroup = self.cgroups.get(&p.pid).cloned();
+        }
+    }
+
+    /// 采集器中的原始进程表，不含前台标记、cgroup 和空闲时长
+    fn snapshot(&self) -> Vec<ProcessInfo> {
+        let (sys, users) = match &self.collector {
+            Collector::Sysinfo(sys, users) => (sys, users),
+            Collector::Procfs(collector) => return collector.processes().to_vec(),
+        };
+
+        sys.processes().values().map(|proc| {
+            let pid = proc.pid().as_u32();
+            let uid = proc.user_id().map(|uid| **uid);
+            ProcessInfo {
+                pid,
+                name: proc.name().to_string_lossy().into_owned(),
+                cmd: proc.cmd().iter().map(|s| s.to_string_lossy().into_owned()).collect(),
+                rss_mb: proc.memory() / BYTES_PER_MB,
+                cpu_usage: proc.cpu_usage(),
+                is_frontmost: false,
+                ppid: proc.parent().map(|p| p.as_u32()),
+                uid,
+                user: uid.and_then(|uid| users.cache.get(&uid).cloned().flatten()),
+                start_time: Some(proc.start_time()).filter(|&t| t > 0),
+                state: proc.status().into(),
+                threads: proc.tasks().map(|tasks| tasks.len() as u32),
+                virtual_mb: proc.virtual_memory() / BYTES_PER_MB,
+                exe: proc.exe().map(Path::to_path_buf),
+                swap_mb: None,
+                pss_mb: None,
+                uss_mb: None,
+                idle_secs: None,
+                cgroup: None,
+            }
+        }).collect()
+    }
+
+    /// 至少刷新过两次后 CPU 使用率才有意义
+    pub fn cpu_ready(&self) -> bool {
+        self.refreshes >= 2
+    }
 
-    sys.processes().values().map(|proc| {
-        let pid = proc.pid().as_u32();
-        ProcessInfo {
-            pid,
-            name: proc.name().to_string_lossy().into_owned(),
-            cmd: proc.cmd().iter().map(|s| s.to_string_lossy().into_owned()).collect(),
-            rss_mb: proc.memory() / BYTES_PER_MB,
-            cpu_usage: proc.cpu_usage(),
-            is_frontmost: frontmost_pid.map_or(false, |p| p == pid),
+    /// 刷新两次（间隔 sysinfo 要求的最小时间），得到带有效 CPU 使用率的快照
+    pub fn refresh_with_cpu(&mut self) -> Vec<ProcessInfo> {
+        if !self.cpu_ready() {
+            self.refresh();
+            std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
         }
-    }).collect()
+        self.refresh()
+    }
+}
+
+/// 一次性获取进程列表；需要反复采样或 CPU 使用率时使用 [`ProcessMonitor`]
+pub fn get_all_processes() -> Vec<ProcessInfo> {
+    ProcessMonitor::new().refresh()
 }
 
-pub fn sort_and_take_processes(mut processes: Vec<ProcessInfo>, n: usize) -> Vec<ProcessInfo> {
-    processes.sort_by(|a, b| b.rss_mb.cmp(&a.rss_mb));
+pub fn sort_and_take_processes(processes: Vec<ProcessInfo>, n: usize) -> Vec<ProcessInfo> {
+    sort_and_take_processes_by(processes, n, MemoryMetric::Rss)
+}
+
+/// 按指定内存口径降序取前 n 个
+pub fn sort_and_take_processes_by(mut processes: Vec<ProcessInfo>, n: usize, metric: MemoryMetric) -> Vec<ProcessInfo> {
+    processes.sort_by_key(|p| std::cmp::Reverse(p.memory_mb(metric)));
     processes.into_iter().take(n).collect()
 }
 
@@ -50,6 +473,7 @@ pub fn sort_and_take_processes(mut processes: Vec<ProcessInfo>, n: usize) -> Vec
 mod tests {
     use super::*;
     use std::process;
+    use crate::procfs::TerminalStat;
 
     #[test]
     fn can_get_all_processes() {
@@ -64,21 +488,96 @@ mod tests {
         assert_eq!(info.pid, current_pid);
         assert!(info.rss_mb > 0);
         assert!(!info.name.is_empty());
+        assert!(info.ppid.is_some());
+        assert_eq!(info.uid, Some(unsafe { libc::getuid() }));
+        assert!(info.user.is_some());
+        assert!(info.start_time.is_some());
+        assert!(info.virtual_mb >= info.rss_mb);
+        // 选定时记录的身份与发送信号前重新读取的一致
+        let identity = crate::termination::ProcessIdentity::current(current_pid).unwrap();
+        assert!(crate::termination::ProcessIdentity::of(info).matches(&identity));
+    }
+
+    #[test]
+    fn can_map_process_states() {
+        assert_eq!(ProcessState::from_proc_char('R'), ProcessState::Running);
+        assert_eq!(ProcessState::from_proc_char('Z'), ProcessState::Zombie);
+        assert_eq!(ProcessState::from_proc_char('?'), ProcessState::Unknown);
+        assert_eq!(ProcessState::from(ProcessStatus::Sleep), ProcessState::Sleeping);
+        assert_eq!(lookup_user_name(0).as_deref(), Some("root"));
+    }
+
+    #[test]
+    fn monitor_keeps_state_between_refreshes() {
+        let mut monitor = ProcessMonitor::new();
+        assert!(!monitor.cpu_ready());
+
+        let first = monitor.refresh();
+        assert!(first.iter().any(|p| p.pid == process::id()));
+
+        let second = monitor.refresh_with_cpu();
+        assert!(monitor.cpu_ready());
+        assert!(second.iter().any(|p| p.pid == process::id()));
+        assert!(second.iter().all(|p| p.cpu_usage >= 0.0));
+        assert_eq!(monitor.processes().len(), second.len());
+    }
+
+    struct FixedForeground(u32);
+
+    impl ForegroundDetector for FixedForeground {
+        fn name(&self) -> &'static str {
+            "fixed"
+        }
+
+        fn frontmost_pids(&self, _processes: &[ProcessInfo], _terminals: Option<&HashMap<u32, TerminalStat>>) -> HashSet<u32> {
+            HashSet::from([self.0])
+        }
     }
 
     #[test]
-    fn can_get_frontmost_pid() {
-        let pid = get_frontmost_pid();
-        if let Some(pid) = pid {
-            assert!(pid > 0);
+    fn monitor_can_use_procfs_backend() {
+        let root = crate::procfs::tests::fake_proc_root("monitor");
+        crate::procfs::tests::write_fake_process(&root, 4242, "worker", 1, 2560, 0);
+
+        let mut monitor = ProcessMonitor::with_backend(ProcessBackend::Procfs(root.clone()));
+        assert_eq!(monitor.backend_name(), "procfs");
+        assert!(!monitor.refresh()[0].is_frontmost);
+
+        monitor.set_foreground_detector(Box::new(FixedForeground(4242)));
+        let processes = monitor.refresh();
+        assert_eq!(processes.len(), 1);
+        assert_eq!(processes[0].name, "worker");
+        assert!(processes[0].is_frontmost);
+        assert_eq!(monitor.processes(), processes);
+
+        // cgroup 只在开启后读取
+        std::fs::write(root.join("4242").join("cgroup"), "0::/system.slice/worker.service\n").unwrap();
+        assert_eq!(monitor.refresh()[0].cgroup, None);
+        monitor.set_read_cgroups(true);
+        let cgroup = monitor.refresh()[0].cgroup.clone();
+        if cfg!(target_os = "linux") {
+            assert_eq!(cgroup.unwrap().unit.as_deref(), Some("worker.service"));
         }
+
+        std::fs::remove_dir_all(&root).unwrap();
+        // procfs 不可读时回退到 sysinfo
+        assert!(!monitor.refresh().is_empty());
+        assert_eq!(monitor.backend_name(), "sysinfo");
+    }
+
+    #[test]
+    fn can_select_process_backend_by_name() {
+        let root = Path::new("/proc");
+        assert_eq!(process_backend_by_name("sysinfo", root), Ok(ProcessBackend::Sysinfo));
+        assert_eq!(process_backend_by_name("auto", root), Ok(ProcessBackend::default_for_platform()));
+        assert!(process_backend_by_name("bogus", root).is_err());
     }
 
     #[test]
     fn can_sort_and_take() {
-        let p1 = ProcessInfo { pid: 1, name: "p1".to_string(), cmd: vec![], rss_mb: 100, cpu_usage: 0.0, is_frontmost: false };
-        let p2 = ProcessInfo { pid: 2, name: "p2".to_string(), cmd: vec![], rss_mb: 300, cpu_usage: 0.0, is_frontmost: false };
-        let p3 = ProcessInfo { pid: 3, name: "p3".to_string(), cmd: vec![], rss_mb: 200, cpu_usage: 0.0, is_frontmost: false };
+        let p1 = ProcessInfo { pid: 1, name: "p1".to_string(), rss_mb: 100, ..Default::default() };
+        let p2 = ProcessInfo { pid: 2, name: "p2".to_string(), rss_mb: 300, ..Default::default() };
+        let p3 = ProcessInfo { pid: 3, name: "p3".to_string(), rss_mb: 200, ..Default::default() };
         let processes = vec![p1.clone(), p2.clone(), p3.clone()];
 
         let sorted = sort_and_take_processes(processes, 2);
@@ -86,4 +585,21 @@ mod tests {
         assert_eq!(sorted[0], p2);
         assert_eq!(sorted[1], p3);
     }
+
+    #[test]
+    fn can_sort_by_memory_metric() {
+        // 共享库很多的进程 RSS 大但 USS 小
+        let helper = ProcessInfo { pid: 1, name: "helper".to_string(), rss_mb: 500, pss_mb: Some(120), uss_mb: Some(40), ..Default::default() };
+        let worker = ProcessInfo { pid: 2, name: "worker".to_string(), rss_mb: 300, pss_mb: Some(280), uss_mb: Some(260), ..Default::default() };
+        let unknown = ProcessInfo { pid: 3, name: "unknown".to_string(), rss_mb: 200, ..Default::default() };
+        let processes = vec![helper, worker, unknown];
+
+        let by_rss = sort_and_take_processes_by(processes.clone(), 3, MemoryMetric::Rss);
+        assert_eq!(by_rss.iter().map(|p| p.pid).collect::<Vec<_>>(), vec![1, 2, 3]);
+        let by_uss = sort_and_take_processes_by(processes, 3, MemoryMetric::Uss);
+        assert_eq!(by_uss.iter().map(|p| p.pid).collect::<Vec<_>>(), vec![2, 3, 1]);
+
+        assert_eq!("PSS".parse::<MemoryMetric>(), Ok(MemoryMetric::Pss));
+        assert!("vss".parse::<MemoryMetric>().is_err());
+    }
 }
\ No newline at end of file
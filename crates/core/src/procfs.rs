@@ -0,0 +1,492 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use crate::processes::{ProcessInfo, ProcessState, UserNames};
+use crate::BYTES_PER_MB;
+
+/// `/proc/<pid>/stat` 中用到的字段
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProcStat {
+    pub pid: u32,
+    pub comm: String,
+    pub state: char,
+    pub ppid: u32,
+    /// 进程组
+    pub pgrp: u32,
+    /// 控制终端设备号，0 表示没有终端
+    pub tty_nr: u64,
+    /// 控制终端的前台进程组，没有终端时为 -1
+    pub tpgid: i32,
+    /// 用户态 + 内核态 CPU 时间（clock ticks）
+    pub cpu_ticks: u64,
+    pub num_threads: u32,
+    /// 系统启动后的 clock ticks
+    pub start_ticks: u64,
+    pub vsize_bytes: u64,
+    pub rss_pages: u64,
+}
+
+/// stat 中与控制终端有关的字段，采集时一并保存，前台检测不必再读一遍
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TerminalStat {
+    pub ppid: u32,
+    pub pgrp: u32,
+    pub tty_nr: u64,
+    pub tpgid: i32,
+}
+
+impl TerminalStat {
+    pub fn of(stat: &ProcStat) -> Self {
+        Self { ppid: stat.ppid, pgrp: stat.pgrp, tty_nr: stat.tty_nr, tpgid: stat.tpgid }
+    }
+
+    /// 进程属于其控制终端的前台进程组
+    pub fn is_foreground(&self) -> bool {
+        self.tty_nr != 0 && self.tpgid > 0 && self.pgrp == self.tpgid as u32
+    }
+}
+
+/// 解析 stat 行。comm 可以包含空格和括号，所以以最后一个 ')' 分隔
+pub fn parse_stat(content: &str) -> Result<ProcStat, String> {
+    let open = content.find('(').ok_or("Malformed stat: missing '('")?;
+    let close = content.rfind(')').ok_or("Malformed stat: missing ')'")?;
+    let pid = content[..open].trim().parse().map_err(|_| "Malformed stat: invalid pid")?;
+    let comm = content[open + 1..close].to_string();
+
+    // 字段从 state（第 3 个字段）开始编号为 0
+    let fields: Vec<&str> = content[close + 1..].split_whitespace().collect();
+    let field = |index: usize| -> Result<u64, String> {
+        fields.get(index)
+            .ok_or_else(|| format!("Malformed stat: missing field {}", index + 3))?
+            .parse()
+            .map_err(|_| format!("Malformed stat: invalid field {}", index + 3))
+    };
+
+    Ok(ProcStat {
+        pid,
+        comm,
+        state: fields.first().and_then(|s| s.chars().next()).ok_or("Malformed stat: missing state")?,
+        ppid: field(1)? as u32,
+        pgrp: field(2)? as u32,
+        tty_nr: field(4)?,
+        tpgid: fields.get(5)
+            .and_then(|v| v.parse().ok())
+            .ok_or("Malformed stat: invalid field 8")?,
+        cpu_ticks: field(11)? + field(12)?,
+        num_threads: field(17)? as u32,
+        start_ticks: field(19)?,
+        vsize_bytes: field(20)?,
+        rss_pages: field(21)?,
+    })
+}
+
+/// `/proc/<pid>/statm` 的第二个字段：常驻页数
+pub fn parse_statm_resident(content: &str) -> Option<u64> {
+    content.split_whitespace().nth(1)?.parse().ok()
+}
+
+/// `/proc/<pid>/status` 中用到的字段
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProcStatus {
+    /// 不像 comm 那样受括号影响
+    pub name: Option<String>,
+    /// 真实 uid
+    pub uid: Option<u32>,
+    pub swap_kb: Option<u64>,
+}
+
+pub fn parse_status(content: &str) -> ProcStatus {
+    let mut status = ProcStatus::default();
+    for line in content.lines() {
+        let Some((key, value)) = line.split_once(':') else { continue };
+        match key {
+            "Name" => status.name = Some(value.trim().to_string()),
+            "Uid" => status.uid = value.split_whitespace().next().and_then(|v| v.parse().ok()),
+            "VmSwap" => status.swap_kb = value.split_whitespace().next().and_then(|v| v.parse().ok()),
+            _ => {}
+        }
+    }
+    status
+}
+
+/// `/proc/<pid>/smaps_rollup` 中用到的字段（kB）
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SmapsRollup {
+    pub rss_kb: u64,
+    pub pss_kb: u64,
+    pub private_clean_kb: u64,
+    pub private_dirty_kb: u64,
+    pub swap_kb: u64,
+}
+
+impl SmapsRollup {
+    /// USS：独占的干净页和脏页
+    pub fn uss_kb(&self) -> u64 {
+        self.private_clean_kb + self.private_dirty_kb
+    }
+}
+
+/// 解析 smaps_rollup；没有 Pss 行时视为格式错误
+pub fn parse_smaps_rollup(content: &str) -> Option<SmapsRollup> {
+    let mut rollup = SmapsRollup::default();
+    let mut has_pss = false;
+    for line in content.lines() {
+        let Some((key, value)) = line.split_once(':') else { continue };
+        let Some(kb) = value.split_whitespace().next().and_then(|v| v.parse().ok()) else { continue };
+        match key {
+            "Rss" => rollup.rss_kb = kb,
+            "Pss" => {
+                rollup.pss_kb = kb;
+                has_pss = true;
+            }
+            "Private_Clean" => rollup.private_clean_kb = kb,
+            "Private_Dirty" => rollup.private_dirty_kb = kb,
+            "Swap" => rollup.swap_kb = kb,
+            _ => {}
+        }
+    }
+    has_pss.then_some(rollup)
+}
+
+/// `/proc/stat` 中的 btime：系统启动时间（Unix 时间戳，秒）
+pub fn parse_boot_time(content: &str) -> Option<u64> {
+    content.lines().find_map(|line| line.strip_prefix("btime"))?.trim().parse().ok()
+}
+
+fn parse_cmdline(raw: &[u8]) -> Vec<String> {
+    raw.split(|&b| b == 0)
+        .filter(|arg| !arg.is_empty())
+        .map(|arg| String::from_utf8_lossy(arg).into_owned())
+        .collect()
+}
+
+fn page_size() -> u64 {
+    let size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+    if size > 0 { size as u64 } else { 4096 }
+}
+
+pub(crate) fn clock_ticks_per_second() -> f64 {
+    let ticks = unsafe { libc::sysconf(libc::_SC_CLK_TCK) };
+    if ticks > 0 { ticks as f64 } else { 100.0 }
+}
+
+/// 进程在扫描过程中退出时读取会返回 NotFound 或 ESRCH，这类错误直接跳过该进程
+fn vanished(err: &io::Error) -> bool {
+    err.kind() == io::ErrorKind::NotFound || err.raw_os_error() == Some(libc::ESRCH)
+}
+
+/// 直接读取 procfs 的进程采集器，不经过 sysinfo。读取缓冲区在各进程间复用，
+/// CPU 使用率由相邻两次采集的 CPU ticks 差值计算
+pub struct ProcfsCollector {
+    root: PathBuf,
+    page_size: u64,
+    clock_ticks: f64,
+    users: UserNames,
+    /// 是否读取 smaps_rollup 以得到 PSS/USS
+    read_smaps: bool,
+    /// pid -> (启动时间, 累计 CPU ticks)，启动时间用于识别 PID 复用
+    previous: HashMap<u32, (u64, u64)>,
+    last_sample: Option<Instant>,
+    last: Vec<ProcessInfo>,
+    /// 上次采集时各进程的终端字段
+    terminals: HashMap<u32, TerminalStat>,
+    text: String,
+    bytes: Vec<u8>,
+}
+
+impl ProcfsCollector {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            page_size: page_size(),
+            clock_ticks: clock_ticks_per_second(),
+            users: UserNames::default(),
+            read_smaps: false,
+            previous: HashMap::new(),
+            last_sample: None,
+            last: Vec::new(),
+            terminals: HashMap::new(),
+            text: String::with_capacity(1024),
+            bytes: Vec::with_capacity(256),
+        }
+    }
+
+    /// 开启后每个进程多读一次 smaps_rollup，内核需要遍历页表，代价明显高于 statm
+    pub fn set_read_smaps(&mut self, read_smaps: bool) {
+        self.read_smaps = read_smaps;
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// 上次采集时各进程的终端字段
+    pub fn terminals(&self) -> &HashMap<u32, TerminalStat> {
+        &self.terminals
+    }
+
+    /// 上次采集的结果
+    pub fn processes(&self) -> &[ProcessInfo] {
+        &self.last
+    }
+
+    /// 扫描 root 下所有数字目录，结果保存在采集器中（即 [`Self::processes`]）
+    pub fn collect(&mut self) -> io::Result<&[ProcessInfo]> {
+        let now = Instant::now();
+        let elapsed = self.last_sample.map(|t| now.duration_since(t).as_secs_f64()).unwrap_or(0.0);
+
+        let boot_time = fs::read_to_string(self.root.join("stat")).ok().as_deref().and_then(parse_boot_time);
+        let mut processes = Vec::with_capacity(self.last.len().max(64));
+        let mut current = HashMap::with_capacity(self.previous.len().max(64));
+        let mut terminals = HashMap::with_capacity(self.terminals.len().max(64));
+
+        for entry in fs::read_dir(&self.root)? {
+            let Ok(entry) = entry else { continue };
+            let Some(pid) = entry.file_name().to_str().and_then(|name| name.parse::<u32>().ok()) else {
+                continue;
+            };
+
+            match self.read_process(pid, elapsed, boot_time) {
+                Ok((info, stat)) => {
+                    current.insert(pid, (stat.start_ticks, stat.cpu_ticks));
+                    terminals.insert(pid, TerminalStat::of(&stat));
+                    processes.push(info);
+                }
+                Err(e) if vanished(&e) => continue,
+                Err(e) if e.kind() == io::ErrorKind::InvalidData => continue,
+                Err(e) if e.kind() == io::ErrorKind::PermissionDenied => continue,
+                Err(e) => return Err(e),
+            }
+        }
+
+        self.previous = current;
+        self.terminals = terminals;
+        self.last_sample = Some(now);
+        self.last = processes;
+        Ok(&self.last)
+    }
+
+    fn read_file(&mut self, pid: u32, file: &str) -> io::Result<()> {
+        self.text.clear();
+        fs::File::open(self.root.join(pid.to_string()).join(file))?.read_to_string(&mut self.text)?;
+        Ok(())
+    }
+
+    fn read_process(&mut self, pid: u32, elapsed: f64, boot_time: Option<u64>) -> io::Result<(ProcessInfo, ProcStat)> {
+        self.read_file(pid, "stat")?;
+        let stat = parse_stat(&self.text).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        self.read_file(pid, "status")?;
+        let status = parse_status(&self.text);
+
+        self.read_file(pid, "statm")?;
+        let resident_pages = parse_statm_resident(&self.text).unwrap_or(stat.rss_pages);
+
+        self.bytes.clear();
+        fs::File::open(self.root.join(pid.to_string()).join("cmdline"))?.read_to_end(&mut self.bytes)?;
+        let cmd = parse_cmdline(&self.bytes);
+        // 其他用户的进程和内核线程读不到 exe
+        let exe = fs::read_link(self.root.join(pid.to_string()).join("exe")).ok();
+
+        // smaps_rollup 需要 4.14+ 内核，且其他用户的进程不可读，读不到时只保留 RSS
+        let rollup = if self.read_smaps && self.read_file(pid, "smaps_rollup").is_ok() {
+            parse_smaps_rollup(&self.text)
+        } else {
+            None
+        };
+
+        let cpu_usage = match self.previous.get(&pid) {
+            Some(&(start, ticks)) if start == stat.start_ticks && elapsed > 0.0 => {
+                let seconds = stat.cpu_ticks.saturating_sub(ticks) as f64 / self.clock_ticks;
+                (seconds / elapsed * 100.0) as f32
+            }
+            _ => 0.0,
+        };
+
+        let info = ProcessInfo {
+            pid,
+            name: status.name.unwrap_or_else(|| stat.comm.clone()),
+            cmd,
+            rss_mb: resident_pages * self.page_size / BYTES_PER_MB,
+            cpu_usage,
+            is_frontmost: false,
+            ppid: Some(stat.ppid),
+            uid: status.uid,
+            user: status.uid.and_then(|uid| self.users.get(uid)),
+            start_time: boot_time.map(|boot| boot + (stat.start_ticks as f64 / self.clock_ticks) as u64),
+            state: ProcessState::from_proc_char(stat.state),
+            threads: Some(stat.num_threads),
+            virtual_mb: stat.vsize_bytes / BYTES_PER_MB,
+            exe,
+            swap_mb: rollup.as_ref().map(|r| r.swap_kb).or(status.swap_kb).map(|kb| kb / 1024),
+            pss_mb: rollup.as_ref().map(|r| r.pss_kb / 1024),
+            uss_mb: rollup.as_ref().map(|r| r.uss_kb() / 1024),
+            idle_secs: None,
+            cgroup: None,
+        };
+        Ok((info, stat))
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// 在临时目录中构造一个只含指定进程的 /proc
+    pub(crate) fn fake_proc_root(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("rambo-proc-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        root
+    }
+
+    pub(crate) fn write_fake_process(root: &Path, pid: u32, comm: &str, ppid: u32, rss_pages: u64, cpu_ticks: u64) {
+        let dir = root.join(pid.to_string());
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("stat"),
+            format!("{} ({}) S {} {} {} 0 -1 4194304 100 0 0 0 {} 0 0 0 20 0 3 0 5000 {} {} 18446744073709551615 0 0 0 0 0 0 0 0 0 0 0 0 17 2 0 0 0 0 0\n",
+                    pid, comm, ppid, pid, pid, cpu_ticks, 1024 * 1024 * 100, rss_pages),
+        ).unwrap();
+        fs::write(
+            dir.join("status"),
+            format!("Name:\t{}\nState:\tS (sleeping)\nPPid:\t{}\nUid:\t0\t0\t0\t0\nVmSwap:\t    2048 kB\n", comm, ppid),
+        ).unwrap();
+        fs::write(dir.join("statm"), format!("25600 {} 100 10 0 500 0\n", rss_pages)).unwrap();
+        fs::write(dir.join("cmdline"), format!("/usr/bin/{}\0--flag\0", comm)).unwrap();
+    }
+
+    pub(crate) fn write_fake_smaps(root: &Path, pid: u32, rss_kb: u64, pss_kb: u64, private_kb: u64) {
+        fs::write(
+            root.join(pid.to_string()).join("smaps_rollup"),
+            format!("00400000-7ffd0000 ---p 00000000 00:00 0                          [rollup]\n\
+                     Rss:            {} kB\nPss:            {} kB\nPss_Anon:       {} kB\n\
+                     Shared_Clean:   0 kB\nPrivate_Clean:  {} kB\nPrivate_Dirty:  {} kB\nSwap:           4096 kB\n",
+                    rss_kb, pss_kb, pss_kb, private_kb / 2, private_kb - private_kb / 2),
+        ).unwrap();
+    }
+
+    #[test]
+    fn can_parse_stat_with_tricky_comm() {
+        let stat = parse_stat("4242 (Web (Content) 1) R 1 4242 4242 0 -1 0 0 0 0 0 150 50 0 0 20 0 12 0 98765 2147483648 51200 0").unwrap();
+        assert_eq!(stat.pid, 4242);
+        assert_eq!(stat.comm, "Web (Content) 1");
+        assert_eq!(stat.state, 'R');
+        assert_eq!(stat.ppid, 1);
+        assert_eq!(stat.pgrp, 4242);
+        assert_eq!(stat.tty_nr, 0);
+        assert_eq!(stat.tpgid, -1);
+        assert_eq!(stat.cpu_ticks, 200);
+        assert_eq!(stat.num_threads, 12);
+        assert_eq!(stat.start_ticks, 98765);
+        assert_eq!(stat.vsize_bytes, 2147483648);
+        assert_eq!(stat.rss_pages, 51200);
+
+        assert!(parse_stat("garbage").is_err());
+        assert!(parse_stat("1 (init) S 0").is_err());
+    }
+
+    #[test]
+    fn can_parse_status_and_boot_time() {
+        let status = parse_status("Name:\tmy app\nUid:\t1000\t1000\t1000\t1000\nVmSwap:\t   10240 kB\n");
+        assert_eq!(status.name.as_deref(), Some("my app"));
+        assert_eq!(status.uid, Some(1000));
+        assert_eq!(status.swap_kb, Some(10240));
+        assert_eq!(parse_status("Name:\tkthreadd\n").swap_kb, None);
+
+        assert_eq!(parse_boot_time("cpu  1 2 3\nbtime 1700000000\nprocesses 10\n"), Some(1700000000));
+        assert_eq!(parse_boot_time("cpu  1 2 3\n"), None);
+    }
+
+    #[test]
+    fn collects_synthetic_proc_tree() {
+        let root = fake_proc_root("collect");
+        write_fake_process(&root, 100, "editor", 1, 51200, 0);
+        write_fake_process(&root, 200, "my app", 100, 2560, 0);
+        fs::create_dir_all(root.join("self")).unwrap();
+        fs::write(root.join("meminfo"), "MemTotal: 1 kB\n").unwrap();
+        fs::write(root.join("stat"), "cpu  1 2 3\nbtime 1700000000\n").unwrap();
+        // 扫描中途消失的进程：目录在，文件已经没了
+        fs::create_dir_all(root.join("300")).unwrap();
+
+        let mut collector = ProcfsCollector::new(&root);
+        let mut processes = collector.collect().unwrap().to_vec();
+        processes.sort_by_key(|p| p.pid);
+
+        assert_eq!(processes.len(), 2);
+        let page_mb = page_size() as f64 / BYTES_PER_MB as f64;
+        assert_eq!(processes[0].name, "editor");
+        assert_eq!(processes[0].rss_mb, (51200.0 * page_mb) as u64);
+        assert_eq!(processes[0].cmd, vec!["/usr/bin/editor", "--flag"]);
+        assert_eq!(processes[1].name, "my app");
+        assert_eq!(processes[1].ppid, Some(100));
+        assert_eq!(processes[1].uid, Some(0));
+        assert_eq!(processes[1].user.as_deref(), Some("root"));
+        assert_eq!(processes[1].state, ProcessState::Sleeping);
+        assert_eq!(processes[1].threads, Some(3));
+        assert_eq!(processes[1].virtual_mb, 100);
+        assert_eq!(processes[1].swap_mb, Some(2));
+        assert_eq!(processes[1].start_time, Some(1700000000 + (5000.0 / clock_ticks_per_second()) as u64));
+        assert_eq!(processes[1].exe, None);
+        assert_eq!(collector.processes().len(), 2);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn can_read_pss_and_uss_from_smaps_rollup() {
+        let rollup = parse_smaps_rollup("Rss:  2048 kB\nPss:  1024 kB\nPrivate_Clean:  100 kB\nPrivate_Dirty:  300 kB\nSwap:  0 kB\n").unwrap();
+        assert_eq!(rollup.pss_kb, 1024);
+        assert_eq!(rollup.uss_kb(), 400);
+        assert!(parse_smaps_rollup("Rss: 10 kB\n").is_none());
+
+        let root = fake_proc_root("smaps");
+        write_fake_process(&root, 100, "helper", 1, 51200, 0);
+        write_fake_smaps(&root, 100, 204800, 102400, 20480);
+        write_fake_process(&root, 200, "no_rollup", 1, 2560, 0);
+
+        let mut collector = ProcfsCollector::new(&root);
+        assert!(collector.collect().unwrap().iter().all(|p| p.pss_mb.is_none()));
+
+        collector.set_read_smaps(true);
+        let mut processes = collector.collect().unwrap().to_vec();
+        processes.sort_by_key(|p| p.pid);
+        assert_eq!(processes[0].pss_mb, Some(100));
+        assert_eq!(processes[0].uss_mb, Some(20));
+        assert_eq!(processes[0].swap_mb, Some(4));
+        assert_eq!(processes[1].pss_mb, None);
+        assert_eq!(processes[1].swap_mb, Some(2));
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn cpu_usage_comes_from_tick_deltas() {
+        let root = fake_proc_root("cpu");
+        write_fake_process(&root, 100, "busy", 1, 1000, 0);
+
+        let mut collector = ProcfsCollector::new(&root);
+        assert_eq!(collector.collect().unwrap()[0].cpu_usage, 0.0);
+
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        write_fake_process(&root, 100, "busy", 1, 1000, 1000);
+        let busy = collector.collect().unwrap();
+        assert!(busy[0].cpu_usage > 0.0);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn can_collect_real_proc() {
+        let mut collector = ProcfsCollector::new("/proc");
+        collector.set_read_smaps(true);
+        let processes = collector.collect().unwrap();
+        let me = processes.iter().find(|p| p.pid == std::process::id()).unwrap();
+        assert!(me.rss_mb > 0);
+        assert!(!me.name.is_empty());
+        assert!(me.uss_mb.unwrap() <= me.rss_mb);
+    }
+}
@@ -0,0 +1,228 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use crate::PressureLevel;
+
+pub const DEFAULT_MEMORY_PSI_PATH: &str = "/proc/pressure/memory";
+
+/// PSI 单行数据：过去 10/60/300 秒内停顿时间占比（%）及累计停顿微秒数
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct PsiAverages {
+    pub avg10: f64,
+    pub avg60: f64,
+    pub avg300: f64,
+    pub total_us: u64,
+}
+
+/// `/proc/pressure/memory` 的 some/full 两行
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct PsiStats {
+    pub some: PsiAverages,
+    pub full: Option<PsiAverages>,
+}
+
+/// 基于 PSI avg10 推导压力等级的停顿阈值（%）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PsiThresholds {
+    pub warning_some_avg10: f64,
+    pub critical_some_avg10: f64,
+    pub critical_full_avg10: f64,
+}
+
+impl Default for PsiThresholds {
+    fn default() -> Self {
+        Self {
+            warning_some_avg10: 10.0,
+            critical_some_avg10: 40.0,
+            critical_full_avg10: 10.0,
+        }
+    }
+}
+
+pub fn read_psi(path: &Path) -> Result<PsiStats, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    parse_psi_str(&content)
+}
+
+pub fn parse_psi_str(content: &str) -> Result<PsiStats, String> {
+    let mut some = None;
+    let mut full = None;
+
+    for line in content.lines() {
+        let mut parts = line.split_whitespace();
+        let kind = match parts.next() {
+            Some(kind) => kind,
+            None => continue,
+        };
+
+        let mut averages = PsiAverages::default();
+        for part in parts {
+            let (key, value) = part.split_once('=')
+                .ok_or_else(|| format!("Malformed PSI field: {}", part))?;
+            let parse_f64 = || value.parse::<f64>().map_err(|_| format!("Invalid PSI value: {}", part));
+            match key {
+                "avg10" => averages.avg10 = parse_f64()?,
+                "avg60" => averages.avg60 = parse_f64()?,
+                "avg300" => averages.avg300 = parse_f64()?,
+                "total" => averages.total_us = value.parse().map_err(|_| format!("Invalid PSI value: {}", part))?,
+                _ => {}
+            }
+        }
+
+        match kind {
+            "some" => some = Some(averages),
+            "full" => full = Some(averages),
+            _ => {}
+        }
+    }
+
+    Ok(PsiStats {
+        some: some.ok_or("PSI data is missing the 'some' line")?,
+        full,
+    })
+}
+
+pub fn derive_pressure_from_psi(psi: &PsiStats, thresholds: &PsiThresholds) -> PressureLevel {
+    let full_avg10 = psi.full.map(|full| full.avg10).unwrap_or(0.0);
+
+    if psi.some.avg10 >= thresholds.critical_some_avg10 || full_avg10 >= thresholds.critical_full_avg10 {
+        PressureLevel::Critical
+    } else if psi.some.avg10 >= thresholds.warning_some_avg10 {
+        PressureLevel::Warning
+    } else {
+        PressureLevel::Normal
+    }
+}
+
+/// PSI 触发器：向 /proc/pressure/memory 写入 "<some|full> <stall_us> <window_us>"，
+/// 之后 poll() 会在窗口内停顿时间超过阈值时被内核唤醒
+#[cfg(target_os = "linux")]
+pub struct PsiTrigger {
+    file: std::fs::File,
+}
+
+#[cfg(target_os = "linux")]
+impl PsiTrigger {
+    pub fn register(path: &Path, spec: &str) -> std::io::Result<Self> {
+        use std::io::{Error, ErrorKind, Write};
+        use std::os::unix::fs::OpenOptionsExt;
+
+        validate_trigger_spec(spec).map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
+
+        let mut file = fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .custom_flags(libc::O_NONBLOCK)
+            .open(path)?;
+
+        let mut buf = spec.trim().as_bytes().to_vec();
+        buf.push(0);
+        file.write_all(&buf)?;
+
+        Ok(Self { file })
+    }
+
+    /// 阻塞直到触发（返回 true）或超时（返回 false）
+    pub fn wait(&self, timeout: std::time::Duration) -> std::io::Result<bool> {
+        use std::os::unix::io::AsRawFd;
+
+        let mut fds = libc::pollfd {
+            fd: self.file.as_raw_fd(),
+            events: libc::POLLPRI,
+            revents: 0,
+        };
+        let timeout_ms = timeout.as_millis().min(i32::MAX as u128) as i32;
+
+        let n = unsafe { libc::poll(&mut fds, 1, timeout_ms) };
+        if n < 0 {
+            let err = std::io::Error::last_os_error();
+            return if err.kind() == std::io::ErrorKind::Interrupted { Ok(false) } else { Err(err) };
+        }
+        if fds.revents & libc::POLLERR != 0 {
+            return Err(std::io::Error::other("PSI trigger source went away"));
+        }
+        Ok(n > 0 && fds.revents & libc::POLLPRI != 0)
+    }
+}
+
+/// 检查触发器格式："some|full <stall_us> <window_us>"，且 stall 不超过 window
+pub fn validate_trigger_spec(spec: &str) -> Result<(), String> {
+    let parts: Vec<&str> = spec.split_whitespace().collect();
+    let [kind, stall, window] = parts.as_slice() else {
+        return Err(format!("Invalid PSI trigger '{}': expected '<some|full> <stall_us> <window_us>'", spec));
+    };
+    if !matches!(*kind, "some" | "full") {
+        return Err(format!("Invalid PSI trigger type '{}'", kind));
+    }
+    let stall: u64 = stall.parse().map_err(|_| format!("Invalid PSI stall threshold '{}'", stall))?;
+    let window: u64 = window.parse().map_err(|_| format!("Invalid PSI window '{}'", window))?;
+    if stall == 0 || stall > window {
+        return Err(format!("PSI stall threshold {}us must be within window {}us", stall, window));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "some avg10=12.50 avg60=4.20 avg300=1.05 total=123456789\n\
+                          full avg10=3.10 avg60=1.00 avg300=0.20 total=23456789\n";
+
+    #[test]
+    fn can_parse_psi() {
+        let psi = parse_psi_str(SAMPLE).unwrap();
+        assert_eq!(psi.some.avg10, 12.50);
+        assert_eq!(psi.some.avg300, 1.05);
+        assert_eq!(psi.some.total_us, 123456789);
+        assert_eq!(psi.full.unwrap().avg60, 1.00);
+    }
+
+    #[test]
+    fn full_line_is_optional() {
+        let psi = parse_psi_str("some avg10=0.00 avg60=0.00 avg300=0.00 total=0\n").unwrap();
+        assert!(psi.full.is_none());
+        assert!(parse_psi_str("").is_err());
+        assert!(parse_psi_str("some avg10=abc").is_err());
+    }
+
+    #[test]
+    fn can_validate_trigger_spec() {
+        assert!(validate_trigger_spec("some 150000 1000000").is_ok());
+        assert!(validate_trigger_spec("full 50000 2000000").is_ok());
+        assert!(validate_trigger_spec("some 150000").is_err());
+        assert!(validate_trigger_spec("partial 1 2").is_err());
+        assert!(validate_trigger_spec("some 2000000 1000000").is_err());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn trigger_registration_fails_without_psi_file() {
+        let result = PsiTrigger::register(Path::new("/nonexistent/pressure/memory"), "some 150000 1000000");
+        assert!(result.is_err());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn can_wait_on_system_trigger() {
+        let path = Path::new(DEFAULT_MEMORY_PSI_PATH);
+        // PSI may be disabled or unavailable in containers
+        if let Ok(trigger) = PsiTrigger::register(path, "some 150000 2000000") {
+            assert!(trigger.wait(std::time::Duration::from_millis(10)).is_ok());
+        }
+    }
+
+    #[test]
+    fn psi_pressure_levels() {
+        let thresholds = PsiThresholds::default();
+        let mut psi = parse_psi_str(SAMPLE).unwrap();
+        assert_eq!(derive_pressure_from_psi(&psi, &thresholds), PressureLevel::Warning);
+
+        psi.some.avg10 = 1.0;
+        assert_eq!(derive_pressure_from_psi(&psi, &thresholds), PressureLevel::Normal);
+
+        psi.full = Some(PsiAverages { avg10: 15.0, ..Default::default() });
+        assert_eq!(derive_pressure_from_psi(&psi, &thresholds), PressureLevel::Critical);
+    }
+}
@@ -0,0 +1,440 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use crate::config::RelaunchConfig;
+use crate::processes::ProcessInfo;
+
+/// 被终止进程能否重新启动
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RelaunchPolicy {
+    /// `rb restore --last` 会自动重启
+    Auto,
+    /// 只能用 `rb restore <id>` 手动重启
+    #[default]
+    Manual,
+    /// 不允许重启（一次性任务、安装程序等）
+    Never,
+}
+
+/// 终止前记录的重启信息
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RelaunchEntry {
+    pub id: u64,
+    /// 同一次终止操作记录的条目共享同一个 batch
+    pub batch: u64,
+    pub terminated_at: DateTime<Utc>,
+    pub pid: u32,
+    pub name: String,
+    pub cmd: Vec<String>,
+    pub exe: Option<PathBuf>,
+    pub cwd: Option<PathBuf>,
+    /// 只包含 `relaunch.capture_env` 允许的变量；重启时覆盖在当前环境之上
+    pub env: Vec<(String, String)>,
+    /// 以 `"*"` 记录了完整环境，重启时不再继承当前环境
+    #[serde(default)]
+    pub env_complete: bool,
+    pub restored_at: Option<DateTime<Utc>>,
+    pub restored_pid: Option<u32>,
+}
+
+/// 在发送信号前读取进程的命令行、工作目录以及 env_keys 中列出的环境变量（`"*"` 表示全部）；
+/// 进程退出后这些信息就读不到了
+pub fn capture(process: &ProcessInfo, env_keys: &[String]) -> RelaunchEntry {
+    let (cwd, mut env) = read_cwd_and_env(process.pid, !env_keys.is_empty());
+    let env_complete = env_keys.iter().any(|key| key == "*");
+    if !env_complete {
+        env.retain(|(key, _)| env_keys.contains(key));
+    }
+    RelaunchEntry {
+        id: 0,
+        batch: 0,
+        terminated_at: Utc::now(),
+        pid: process.pid,
+        name: process.name.clone(),
+        cmd: process.cmd.clone(),
+        exe: process.exe.clone(),
+        cwd,
+        env,
+        env_complete,
+        restored_at: None,
+        restored_pid: None,
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn read_cwd_and_env(pid: u32, with_env: bool) -> (Option<PathBuf>, Vec<(String, String)>) {
+    let dir = PathBuf::from("/proc").join(pid.to_string());
+    let cwd = fs::read_link(dir.join("cwd")).ok();
+    if !with_env {
+        return (cwd, Vec::new());
+    }
+    let env = fs::read(dir.join("environ"))
+        .map(|bytes| parse_environ(bytes.split(|&b| b == 0).map(|var| String::from_utf8_lossy(var).into_owned())))
+        .unwrap_or_default();
+    (cwd, env)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_cwd_and_env(pid: u32, with_env: bool) -> (Option<PathBuf>, Vec<(String, String)>) {
+    use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
+
+    let pid_key = Pid::from_u32(pid);
+    let mut sys = System::new();
+    let mut kind = ProcessRefreshKind::nothing().with_cwd(UpdateKind::Always);
+    if with_env {
+        kind = kind.with_environ(UpdateKind::Always);
+    }
+    sys.refresh_processes_specifics(ProcessesToUpdate::Some(&[pid_key]), true, kind);
+    match sys.process(pid_key) {
+        Some(process) => (
+            process.cwd().map(Path::to_path_buf),
+            parse_environ(process.environ().iter().map(|var| var.to_string_lossy().into_owned())),
+        ),
+        None => (None, Vec::new()),
+    }
+}
+
+fn parse_environ(vars: impl Iterator<Item = String>) -> Vec<(String, String)> {
+    vars.filter_map(|var| {
+        let (key, value) = var.split_once('=')?;
+        (!key.is_empty()).then(|| (key.to_string(), value.to_string()))
+    }).collect()
+}
+
+/// 重启记录，保存在数据目录下的 `relaunch.json`
+#[derive(Debug, Clone)]
+pub struct RelaunchRegistry {
+    path: PathBuf,
+    max_entries: usize,
+    /// capture 时记录的环境变量名
+    env_keys: Vec<String>,
+}
+
+impl RelaunchRegistry {
+    /// 不记录环境变量，见 [`Self::with_env_keys`]
+    pub fn new(path: PathBuf, max_entries: usize) -> Self {
+        Self { path, max_entries, env_keys: Vec::new() }
+    }
+
+    pub fn with_env_keys(mut self, env_keys: Vec<String>) -> Self {
+        self.env_keys = env_keys;
+        self
+    }
+
+    /// 未开启记录或找不到数据目录时返回 None
+    pub fn from_config(config: &RelaunchConfig) -> Option<Self> {
+        if !config.enabled {
+            return None;
+        }
+        let path = match &config.registry_path {
+            Some(path) => path.clone(),
+            None => dirs::data_dir()?.join("rambo").join("relaunch.json"),
+        };
+        Some(Self::new(path, config.max_entries).with_env_keys(config.capture_env.clone()))
+    }
+
+    /// 按本记录的环境变量设置读取进程信息
+    pub fn capture(&self, process: &ProcessInfo) -> RelaunchEntry {
+        capture(process, &self.env_keys)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 按 id 升序返回全部条目；文件不存在时为空
+    pub fn load(&self) -> Result<Vec<RelaunchEntry>, String> {
+        match fs::read_to_string(&self.path) {
+            Ok(content) => serde_json::from_str(&content).map_err(|e| format!("Could not parse relaunch registry: {}", e)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(e) => Err(format!("Could not read relaunch registry: {}", e)),
+        }
+    }
+
+    fn save(&self, entries: &[RelaunchEntry]) -> Result<(), String> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("Could not create relaunch registry directory: {}", e))?;
+        }
+        let json = serde_json::to_string_pretty(entries).map_err(|e| format!("Could not serialize relaunch registry: {}", e))?;
+        // 记录中可能含有环境变量和命令行参数，只允许当前用户读取
+        let tmp = self.path.with_extension("json.tmp");
+        {
+            use std::io::Write;
+            use std::os::unix::fs::OpenOptionsExt;
+            let mut file = fs::OpenOptions::new().write(true).create(true).truncate(true).mode(0o600).open(&tmp)
+                .map_err(|e| format!("Could not write relaunch registry: {}", e))?;
+            file.write_all(json.as_bytes()).map_err(|e| format!("Could not write relaunch registry: {}", e))?;
+        }
+        fs::rename(&tmp, &self.path).map_err(|e| format!("Could not write relaunch registry: {}", e))
+    }
+
+    /// 对旁边的 `relaunch.json.lock` 加排他的建议锁，文件关闭时释放；
+    /// 避免 Daemon 和 `rb kill`/`rb restore` 同时读改写时互相覆盖
+    fn lock(&self) -> Result<fs::File, String> {
+        use std::os::fd::AsRawFd;
+        use std::os::unix::fs::OpenOptionsExt;
+
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("Could not create relaunch registry directory: {}", e))?;
+        }
+        let file = fs::OpenOptions::new().write(true).create(true).truncate(false).mode(0o600)
+            .open(self.path.with_extension("json.lock"))
+            .map_err(|e| format!("Could not lock relaunch registry: {}", e))?;
+        loop {
+            if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX) } == 0 {
+                return Ok(file);
+            }
+            let err = std::io::Error::last_os_error();
+            if err.kind() != std::io::ErrorKind::Interrupted {
+                return Err(format!("Could not lock relaunch registry: {}", err));
+            }
+        }
+    }
+
+    /// 把同一次终止操作的条目作为一个 batch 追加，超出 max_entries 时丢弃最旧的；返回分配的 id
+    pub fn record(&self, captured: Vec<RelaunchEntry>) -> Result<Vec<u64>, String> {
+        if captured.is_empty() {
+            return Ok(Vec::new());
+        }
+        let _lock = self.lock()?;
+        let mut entries = self.load()?;
+        let first_id = entries.last().map_or(1, |e| e.id + 1);
+        let batch = entries.last().map_or(1, |e| e.batch + 1);
+        let mut ids = Vec::new();
+        for (id, mut entry) in (first_id..).zip(captured) {
+            entry.id = id;
+            entry.batch = batch;
+            ids.push(id);
+            entries.push(entry);
+        }
+        let excess = entries.len().saturating_sub(self.max_entries);
+        entries.drain(..excess);
+        self.save(&entries)?;
+        Ok(ids)
+    }
+
+    pub fn mark_restored(&self, id: u64, pid: u32) -> Result<(), String> {
+        let _lock = self.lock()?;
+        let mut entries = self.load()?;
+        let entry = entries.iter_mut().find(|e| e.id == id).ok_or_else(|| format!("No relaunch entry with id {}", id))?;
+        entry.restored_at = Some(Utc::now());
+        entry.restored_pid = Some(pid);
+        self.save(&entries)
+    }
+}
+
+/// 以原始 argv 和工作目录启动进程，不等待其退出；返回新进程的 PID
+pub fn relaunch(entry: &RelaunchEntry) -> Result<u32, String> {
+    use std::os::unix::process::CommandExt;
+
+    let (program, args) = entry.cmd.split_first().ok_or("no command line was recorded")?;
+    let mut command = Command::new(program);
+    command.args(args).stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::null());
+    // 放进独立的进程组，终端的 Ctrl-C 不会波及重启的进程
+    command.process_group(0);
+    if let Some(cwd) = entry.cwd.as_ref().filter(|cwd| cwd.is_dir()) {
+        command.current_dir(cwd);
+    }
+    if entry.env_complete {
+        command.env_clear();
+    }
+    command.envs(entry.env.iter().map(|(k, v)| (k, v)));
+    let mut child = command.spawn().map_err(|e| format!("could not start '{}': {}", program, e))?;
+    let pid = child.id();
+    // 回收子进程，避免 Daemon 等长期运行的调用方留下僵尸进程
+    std::thread::spawn(move || { let _ = child.wait(); });
+    Ok(pid)
+}
+
+/// 要重启的条目
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestoreTarget {
+    /// 最近一次终止操作中策略为 Auto 且尚未重启的条目
+    Last,
+    Id(u64),
+}
+
+/// 一个条目的重启结果
+#[derive(Debug, Clone, Serialize)]
+pub struct RestoreResult {
+    pub id: u64,
+    pub name: String,
+    pub pid: Option<u32>,
+    pub error: Option<String>,
+}
+
+/// 按 target 重启记录中的进程并标记为已重启
+pub fn restore(registry: &RelaunchRegistry, config: &RelaunchConfig, target: RestoreTarget) -> Result<Vec<RestoreResult>, String> {
+    let entries = registry.load()?;
+    let selected: Vec<&RelaunchEntry> = match target {
+        RestoreTarget::Id(id) => vec![entries.iter().find(|e| e.id == id).ok_or_else(|| format!("No relaunch entry with id {}", id))?],
+        RestoreTarget::Last => {
+            let Some(batch) = entries.last().map(|e| e.batch) else {
+                return Ok(Vec::new());
+            };
+            entries.iter().filter(|e| e.batch == batch).collect()
+        }
+    };
+
+    let mut results = Vec::new();
+    for entry in selected {
+        let policy = config.policy_for(&entry.name);
+        let refused = if let Some(pid) = entry.restored_pid {
+            Some(format!("already restored as PID {}", pid))
+        } else if policy == RelaunchPolicy::Never {
+            Some("relaunch policy is never".to_string())
+        } else if target == RestoreTarget::Last && policy != RelaunchPolicy::Auto {
+            Some(format!("relaunch policy is manual; use `rb restore {}`", entry.id))
+        } else {
+            None
+        };
+        let result = match refused {
+            Some(reason) => Err(reason),
+            None => relaunch(entry).and_then(|pid| registry.mark_restored(entry.id, pid).map(|()| pid)),
+        };
+        results.push(RestoreResult {
+            id: entry.id,
+            name: entry.name.clone(),
+            pid: result.as_ref().ok().copied(),
+            error: result.err(),
+        });
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+    use std::time::Duration;
+
+    fn entry(name: &str, cmd: &[&str], cwd: &Path) -> RelaunchEntry {
+        RelaunchEntry {
+            name: name.to_string(),
+            cmd: cmd.iter().map(|s| s.to_string()).collect(),
+            cwd: Some(cwd.to_path_buf()),
+            env: vec![("RAMBO_TEST".to_string(), "restored".to_string())],
+            ..capture(&ProcessInfo { pid: std::process::id(), ..Default::default() }, &[])
+        }
+    }
+
+    #[test]
+    fn captures_cwd_and_environment() {
+        let me = ProcessInfo { pid: std::process::id(), name: "self".to_string(), ..Default::default() };
+        // 默认不记录环境变量
+        let captured = capture(&me, &[]);
+        assert_eq!(captured.cwd, std::env::current_dir().ok());
+        assert!(captured.env.is_empty() && !captured.env_complete);
+
+        let captured = capture(&me, &["PATH".to_string()]);
+        assert_eq!(captured.env.iter().map(|(key, _)| key.as_str()).collect::<Vec<_>>(), vec!["PATH"]);
+        let captured = capture(&me, &["*".to_string()]);
+        assert!(captured.env_complete && captured.env.iter().any(|(key, _)| key == "PATH"));
+    }
+
+    #[test]
+    fn relaunch_keeps_inherited_environment_for_partial_capture() {
+        let dir = std::env::temp_dir().join(format!("rambo-relaunch-env-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        let mut child = Command::new("sleep").arg("30").env("RAMBO_TEST", "restored").spawn().unwrap();
+        let sleeper = ProcessInfo { pid: child.id(), ..Default::default() };
+        // spawn 返回时 exec 可能尚未完成，环境还读不到
+        let mut captured = capture(&sleeper, &["RAMBO_TEST".to_string()]);
+        for _ in 0..50 {
+            if !captured.env.is_empty() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(20));
+            captured = capture(&sleeper, &["RAMBO_TEST".to_string()]);
+        }
+        child.kill().unwrap();
+        child.wait().unwrap();
+        assert_eq!(captured.env, vec![("RAMBO_TEST".to_string(), "restored".to_string())]);
+        assert!(!captured.env_complete);
+
+        let script = "echo \"$PATH\" > env.txt; echo $RAMBO_TEST >> env.txt";
+        let entry = RelaunchEntry { cmd: vec!["sh".to_string(), "-c".to_string(), script.to_string()], cwd: Some(dir.clone()), ..captured };
+        relaunch(&entry).unwrap();
+
+        let output = dir.join("env.txt");
+        for _ in 0..50 {
+            if fs::read_to_string(&output).is_ok_and(|s| s.lines().count() == 2) {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        // 只记录了部分变量时，PATH 等仍从当前环境继承
+        assert_eq!(fs::read_to_string(&output).unwrap(), format!("{}\nrestored\n", std::env::var("PATH").unwrap()));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn records_batches_and_restores_by_policy() {
+        let dir = std::env::temp_dir().join(format!("rambo-relaunch-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let registry = RelaunchRegistry::new(dir.join("relaunch.json"), 3);
+
+        let script = "pwd > restored.txt; echo $RAMBO_TEST >> restored.txt";
+        assert_eq!(registry.record(vec![entry("old", &["true"], &dir)]).unwrap(), vec![1]);
+        let ids = registry.record(vec![entry("server", &["sh", "-c", script], &dir), entry("editor", &["true"], &dir)]).unwrap();
+        assert_eq!(ids, vec![2, 3]);
+        registry.record(vec![entry("build", &["true"], &dir), entry("server", &["sh", "-c", script], &dir)]).unwrap();
+        // 超出上限时丢弃最旧的条目
+        let entries = registry.load().unwrap();
+        assert_eq!(entries.iter().map(|e| e.id).collect::<Vec<_>>(), vec![3, 4, 5]);
+        assert_eq!(entries[1].batch, entries[2].batch);
+
+        let config = RelaunchConfig {
+            policy: BTreeMap::from([("server".to_string(), RelaunchPolicy::Auto), ("editor".to_string(), RelaunchPolicy::Never)]),
+            ..RelaunchConfig::default()
+        };
+        let results = restore(&registry, &config, RestoreTarget::Last).unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(results[0].error.as_deref().unwrap().contains("rb restore 4"));
+        assert!(results[1].pid.is_some(), "{:?}", results[1]);
+
+        let output = dir.join("restored.txt");
+        for _ in 0..50 {
+            if fs::read_to_string(&output).is_ok_and(|s| s.lines().count() == 2) {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        let expected_cwd = dir.canonicalize().unwrap();
+        assert_eq!(fs::read_to_string(&output).unwrap(), format!("{}\nrestored\n", expected_cwd.display()));
+
+        // 已重启的不会重复启动；Never 策略即使指定 id 也拒绝
+        assert!(restore(&registry, &config, RestoreTarget::Last).unwrap()[1].error.as_deref().unwrap().starts_with("already restored"));
+        assert!(restore(&registry, &config, RestoreTarget::Id(3)).unwrap()[0].error.is_some());
+        assert!(restore(&registry, &config, RestoreTarget::Id(4)).unwrap()[0].pid.is_some());
+        assert!(restore(&registry, &config, RestoreTarget::Id(99)).is_err());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn concurrent_records_get_distinct_ids() {
+        let dir = std::env::temp_dir().join(format!("rambo-relaunch-lock-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let registry = RelaunchRegistry::new(dir.join("relaunch.json"), 100);
+
+        let threads: Vec<_> = (0..8).map(|i| {
+            let registry = registry.clone();
+            let cwd = std::env::temp_dir();
+            std::thread::spawn(move || registry.record(vec![entry(&format!("p{}", i), &["true"], &cwd)]).unwrap())
+        }).collect();
+        let mut ids: Vec<u64> = threads.into_iter().flat_map(|t| t.join().unwrap()).collect();
+        ids.sort();
+        assert_eq!(ids, (1..=8).collect::<Vec<_>>());
+        assert_eq!(registry.load().unwrap().len(), 8);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
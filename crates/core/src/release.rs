@@ -1,17 +1,29 @@
-use crate::processes::ProcessInfo;
-use std::collections::HashSet;
+use crate::processes::{get_all_processes, MemoryMetric, ProcessInfo, ProcessMonitor};
+use crate::security::{check_process_safety, SafetyLevel};
+use crate::tree::{group_by_app, AppGroup, ProcessTree};
 use std::process::{Command, ExitStatus};
 use std::time::{Duration, Instant};
-use std::io::ErrorKind;
-use sysinfo::{System, Signal, Pid, ProcessesToUpdate};
-use crate::{MemStats, read_mem_stats};
-use serde::Serialize;
+use std::io::{ErrorKind, Write};
+use std::path::Path;
+use std::process::Stdio;
+use crate::MemStats;
+use crate::config::{Config, ReleaseConfig};
+use crate::measure::ReclaimMeasurement;
+use crate::rules::{CompiledRule, RuleAction, RuleSet};
+use crate::budget::{plan_free, BudgetSelection};
+use crate::stats::{default_provider, provider_from_config, MemoryStatsProvider};
+use crate::termination::{process_alive, terminate_many, DEFAULT_GRACE};
+pub use crate::termination::{terminate_with_grace, ProcessIdentity, TerminationOutcome};
+use crate::strategy::{BoostLevel, BoostPipeline, BoostPlan, BoostStep, PlanKind, RejectedTarget};
+use serde::{Deserialize, Serialize};
 
 
 #[derive(Debug)]
 pub enum BoostError {
     Purge(PurgeError),
     Stats(String),
+    /// 策略的前置条件不满足
+    Precondition(String),
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -21,6 +33,81 @@ pub struct BoostResult {
     pub delta_mb: i64,
     #[serde(with = "humantime_serde")]
     pub duration: Duration,
+    /// 第一个成功执行的缓存释放策略
+    pub method: Option<ReleaseMethod>,
+    /// 流水线中每个策略的执行记录
+    pub steps: Vec<BoostStep>,
+    /// 采样窗口内的回收测量，未开启时为 None
+    pub measurement: Option<ReclaimMeasurement>,
+}
+
+/// 执行保存的计划的结果，包含重新校验时剔除的目标
+#[derive(Debug, Serialize, Clone)]
+pub struct PlanExecution {
+    pub result: BoostResult,
+    pub rejected: Vec<RejectedTarget>,
+}
+
+/// Killer 模式中的一轮
+#[derive(Debug, Serialize, Clone)]
+pub struct KillerRound {
+    pub round: u8,
+    pub title: String,
+    #[serde(flatten)]
+    pub step: BoostStep,
+}
+
+/// Killer 模式的逐轮报告
+#[derive(Debug, Serialize, Clone)]
+pub struct KillerReport {
+    pub before: MemStats,
+    pub after: MemStats,
+    pub delta_mb: i64,
+    #[serde(with = "humantime_serde")]
+    pub duration: Duration,
+    /// 配置中是否允许第二轮终止进程
+    pub termination_enabled: bool,
+    pub rounds: Vec<KillerRound>,
+    pub measurement: Option<ReclaimMeasurement>,
+}
+
+/// 写入 /proc/sys/vm/drop_caches 的值
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DropCachesMode {
+    /// 1: 只释放页缓存
+    PageCache,
+    /// 2: 释放 dentry 和 inode 等 slab 对象
+    Slab,
+    /// 3: 两者都释放
+    All,
+}
+
+impl DropCachesMode {
+    pub fn value(&self) -> u8 {
+        match self {
+            DropCachesMode::PageCache => 1,
+            DropCachesMode::Slab => 2,
+            DropCachesMode::All => 3,
+        }
+    }
+
+    pub fn from_value(value: u8) -> Option<Self> {
+        match value {
+            1 => Some(DropCachesMode::PageCache),
+            2 => Some(DropCachesMode::Slab),
+            3 => Some(DropCachesMode::All),
+            _ => None,
+        }
+    }
+}
+
+/// boost 实际使用的缓存释放方式
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ReleaseMethod {
+    Purge,
+    DropCaches { mode: DropCachesMode },
 }
 
 #[derive(Debug)]
@@ -102,40 +189,255 @@ pub fn purge_with_permission(request_permission: bool) -> Result<(Duration, Exit
     }
 }
 
+/// Linux 上的 purge 等价物：先 sync，再向 `<procfs_root>/sys/vm/drop_caches` 写入模式值
+pub fn drop_caches(mode: DropCachesMode, procfs_root: &Path) -> Result<Duration, PurgeError> {
+    drop_caches_with_permission(mode, procfs_root, false)
+}
+
+pub fn drop_caches_with_permission(mode: DropCachesMode, procfs_root: &Path, request_permission: bool) -> Result<Duration, PurgeError> {
+    let start = Instant::now();
+
+    let target = procfs_root.join("sys/vm/drop_caches");
+    if !target.exists() {
+        return Err(PurgeError::CommandNotFound);
+    }
+
+    // 先把脏页写回，否则 drop_caches 释放不了它们
+    unsafe { libc::sync() };
+
+    let value = format!("{}\n", mode.value());
+    match std::fs::write(&target, &value) {
+        Ok(()) => return Ok(start.elapsed()),
+        Err(e) if e.kind() != ErrorKind::PermissionDenied => return Err(PurgeError::IoError(e)),
+        Err(_) => {}
+    }
+
+    // 直接写入失败，通过 sudo tee 提权
+    let mut sudo = Command::new("sudo");
+    if request_permission {
+        println!("🔐 需要管理员权限来执行内存清理，请输入密码:");
+    } else {
+        sudo.arg("-n"); // 非交互模式
+    }
+
+    let mut child = sudo
+        .arg("tee")
+        .arg(&target)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .spawn()?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(value.as_bytes())?;
+    }
+    let status = child.wait()?;
+
+    if status.success() {
+        Ok(start.elapsed())
+    } else {
+        Err(PurgeError::ExecutionFailed(status))
+    }
+}
+
 pub fn boost() -> Result<BoostResult, BoostError> {
-    let before_stats = read_mem_stats().map_err(BoostError::Stats)?;
+    boost_with(default_provider().as_ref())
+}
 
-    let (duration, _) = purge().map_err(BoostError::Purge)?;
+pub fn boost_with(provider: &dyn MemoryStatsProvider) -> Result<BoostResult, BoostError> {
+    boost_with_config(provider, &ReleaseConfig::default())
+}
 
-    let after_stats = read_mem_stats().map_err(BoostError::Stats)?;
+pub fn boost_with_config(provider: &dyn MemoryStatsProvider, config: &ReleaseConfig) -> Result<BoostResult, BoostError> {
+    BoostPipeline::for_config(config).run(provider)
+}
 
-    let delta = after_stats.free_mb as i64 - before_stats.free_mb as i64;
+/// 按清理等级执行 `[boost]` 中配置的动作
+pub fn boost_with_level(
+    provider: &dyn MemoryStatsProvider,
+    monitor: &mut ProcessMonitor,
+    config: &Config,
+    level: BoostLevel,
+) -> Result<BoostResult, BoostError> {
+    BoostPipeline::for_level(config, level, monitor).run(provider)
+}
+
+/// 生成清理计划（dry-run），不执行任何操作
+pub fn plan_boost(
+    provider: &dyn MemoryStatsProvider,
+    monitor: &mut ProcessMonitor,
+    config: &Config,
+    kind: PlanKind,
+) -> Result<BoostPlan, BoostError> {
+    let pipeline = match kind {
+        PlanKind::Level { level } => BoostPipeline::for_level(config, level, monitor),
+        PlanKind::Killer => BoostPipeline::killer(config, monitor),
+    };
+    pipeline.plan(kind, provider)
+}
 
-    Ok(BoostResult {
-        before: before_stats,
-        after: after_stats,
-        delta_mb: delta,
-        duration,
+/// 执行之前生成的计划；终止目标会按当前进程表重新校验，不再安全的进程被剔除
+pub fn execute_plan(
+    provider: &dyn MemoryStatsProvider,
+    monitor: &mut ProcessMonitor,
+    config: &Config,
+    plan: &BoostPlan,
+) -> Result<PlanExecution, BoostError> {
+    let current = monitor.refresh();
+    let (pipeline, rejected) = BoostPipeline::from_plan(plan, config, &current)?;
+    let result = pipeline.run(provider)?;
+    Ok(PlanExecution { result, rejected })
+}
+
+const KILLER_ROUND_TITLES: [&str; 3] = ["标准内存清理", "终止高内存占用进程", "深度系统缓存清理"];
+
+/// Killer 模式：标准缓存释放、终止通过安全检查的候选进程（需 enable_process_termination）、深度缓存清理
+pub fn killer_boost(config: &Config) -> Result<KillerReport, BoostError> {
+    let provider = provider_from_config(config).unwrap_or_else(|_| default_provider());
+    killer_boost_with(provider.as_ref(), &mut ProcessMonitor::from_config(config).unwrap_or_default(), config)
+}
+
+pub fn killer_boost_with(
+    provider: &dyn MemoryStatsProvider,
+    monitor: &mut ProcessMonitor,
+    config: &Config,
+) -> Result<KillerReport, BoostError> {
+    let result = BoostPipeline::killer(config, monitor).run(provider)?;
+
+    let rounds = result.steps.into_iter().enumerate().map(|(i, step)| KillerRound {
+        round: i as u8 + 1,
+        title: KILLER_ROUND_TITLES[i].to_string(),
+        step,
+    }).collect();
+
+    Ok(KillerReport {
+        before: result.before,
+        after: result.after,
+        delta_mb: result.delta_mb,
+        duration: result.duration,
+        termination_enabled: config.enable_process_termination,
+        rounds,
+        measurement: result.measurement,
     })
 }
 
+/// `rb free` 的执行结果
+#[derive(Debug, Serialize, Clone)]
+pub struct FreeReport {
+    pub selection: BudgetSelection,
+    pub result: BoostResult,
+}
+
+/// 刷新进程表并按 `[free]` 配置选出合计可回收 target_mb 的进程，不执行任何操作
+pub fn select_free_targets(monitor: &mut ProcessMonitor, config: &Config, target_mb: u64) -> BudgetSelection {
+    monitor.set_memory_metric(config.free.metric);
+    plan_free(config, &monitor.refresh(), target_mb)
+}
+
+/// 终止按内存预算选出的进程（需 enable_process_termination），并测量实际回收量
+pub fn free_memory_with(
+    provider: &dyn MemoryStatsProvider,
+    monitor: &mut ProcessMonitor,
+    config: &Config,
+    target_mb: u64,
+) -> Result<FreeReport, BoostError> {
+    let selection = select_free_targets(monitor, config, target_mb);
+    let result = BoostPipeline::for_budget(config, &selection).run(provider)?;
+    Ok(FreeReport { selection, result })
+}
+
 pub fn get_candidate_processes<'a>(
     processes: &'a [ProcessInfo],
     rss_threshold_mb: u64,
-    whitelist: &HashSet<String>,
-    blacklist: &HashSet<String>,
+    rules: &RuleSet,
 ) -> Vec<&'a ProcessInfo> {
-    processes
+    get_candidate_processes_by(processes, &CandidateCriteria::rss(rss_threshold_mb), rules)
+}
+
+/// 候选进程的筛选条件
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CandidateCriteria {
+    pub threshold_mb: u64,
+    /// 阈值使用的内存口径
+    pub metric: MemoryMetric,
+    /// 须持续空闲的时长，None 表示不考虑 CPU
+    pub idle_for: Option<Duration>,
+}
+
+impl CandidateCriteria {
+    pub fn rss(threshold_mb: u64) -> Self {
+        Self { threshold_mb, ..Default::default() }
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            threshold_mb: config.rss_threshold_mb,
+            metric: config.memory_metric,
+            idle_for: (config.idle.min_idle_minutes > 0)
+                .then(|| Duration::from_secs(config.idle.min_idle_minutes * 60)),
+        }
+    }
+
+    fn matches(&self, memory_mb: u64, idle_secs: Option<u64>) -> bool {
+        if memory_mb < self.threshold_mb {
+            return false;
+        }
+        match self.idle_for {
+            // 没有跟踪过 CPU 的进程无法判断是否空闲
+            Some(idle_for) => idle_secs.is_some_and(|secs| secs >= idle_for.as_secs()),
+            None => true,
+        }
+    }
+}
+
+/// 匹配规则对应的排序权重，protect 和 never_suggest 返回 None 表示排除
+fn rule_rank(rule: Option<&CompiledRule>) -> Option<u8> {
+    match rule.map(CompiledRule::action) {
+        Some(RuleAction::Protect | RuleAction::NeverSuggest) => None,
+        Some(RuleAction::AutoTerminate) => Some(2),
+        Some(RuleAction::Prefer) => Some(1),
+        None => Some(0),
+    }
+}
+
+/// 按条件和规则筛选候选进程：auto_terminate 规则匹配的进程不受阈值和空闲条件限制；
+/// 排序依次按规则（auto_terminate、prefer、未匹配）、空闲时长、内存降序
+pub fn get_candidate_processes_by<'a>(
+    processes: &'a [ProcessInfo],
+    criteria: &CandidateCriteria,
+    rules: &RuleSet,
+) -> Vec<&'a ProcessInfo> {
+    let mut candidates: Vec<(u8, &ProcessInfo)> = processes
+        .iter()
+        .filter(|p| !p.is_frontmost)
+        .filter_map(|p| {
+            let rank = rule_rank(rules.matching(p))?;
+            let auto = rank == 2;
+            (auto || criteria.matches(p.memory_mb(criteria.metric), p.idle_secs)).then_some((rank, p))
+        })
+        .collect();
+    candidates.sort_by_key(|&(rank, p)| std::cmp::Reverse((rank, p.idle_secs.unwrap_or(0), p.memory_mb(criteria.metric))));
+    candidates.into_iter().map(|(_, p)| p).collect()
+}
+
+/// 按应用筛选候选：阈值比较汇总内存，规则的名称条件匹配应用名或根进程名，空闲时长取成员中最短的
+pub fn get_candidate_groups<'a>(
+    groups: &'a [AppGroup],
+    criteria: &CandidateCriteria,
+    rules: &RuleSet,
+) -> Vec<&'a AppGroup> {
+    let mut candidates: Vec<(u8, &AppGroup)> = groups
         .iter()
-        .filter(|p| {
-            if p.rss_mb < rss_threshold_mb { return false; }
-            if p.is_frontmost { return false; }
-            if blacklist.contains(&p.name) { return false; }
-            if !whitelist.is_empty() && !whitelist.contains(&p.name) { return false; }
-            true
+        .filter(|g| !g.is_frontmost)
+        .filter_map(|g| {
+            let rank = rule_rank(rules.matching_group(g))?;
+            let auto = rank == 2;
+            (auto || criteria.matches(g.memory_mb(criteria.metric), g.idle_secs)).then_some((rank, g))
         })
-        .collect()
+        .collect();
+    candidates.sort_by_key(|&(rank, g)| std::cmp::Reverse((rank, g.idle_secs.unwrap_or(0), g.memory_mb(criteria.metric))));
+    candidates.into_iter().map(|(_, g)| g).collect()
 }
+
 pub fn check_sudo_permissions() -> Result<bool, std::io::Error> {
     let output = Command::new("sudo")
         .arg("-n")
@@ -175,39 +477,69 @@ pub fn get_permission_status() -> String {
     }
 }
 
-pub fn terminate(pid: u32, force: bool) -> bool {
-    let mut sys = System::new();
-    sys.refresh_processes(ProcessesToUpdate::All, true);
-
-    let sysinfo_pid = Pid::from_u32(pid);
-
-    if let Some(process) = sys.process(sysinfo_pid) {
-        // 尝试优雅终止
-        if process.kill_with(Signal::Term).unwrap_or(false) {
-            std::thread::sleep(Duration::from_secs(2));
-            sys.refresh_processes(ProcessesToUpdate::All, true);
+/// 确认 PID 仍属于选定的进程后发送 SIGTERM，等待默认宽限期后按 force 决定是否 SIGKILL；
+/// 宽限期可配置的版本见 [`terminate_with_grace`]
+pub fn terminate(target: &ProcessIdentity, force: bool) -> TerminationOutcome {
+    terminate_with_grace(target, force, DEFAULT_GRACE)
+}
 
-            // 检查进程是否已终止
-            if sys.process(sysinfo_pid).is_none() {
-                return true;
-            }
+/// 终止以 pid 为根的整个应用（含辅助进程；pid 不是应用根时只含它的进程子树）：
+/// 先终止根进程，再同时终止仍未退出的成员。未通过安全检查（Dangerous/Forbidden）的成员会被跳过，记为 StillAlive；
+/// 根进程身份不符时不处理任何成员
+pub fn terminate_app(root: &ProcessIdentity, force: bool, grace: Duration) -> Vec<(u32, TerminationOutcome)> {
+    let pid = root.pid;
+    if let Err(outcome) = root.verify() {
+        return vec![(pid, outcome)];
+    }
+    let processes = get_all_processes();
+    let members: Vec<ProcessInfo> = match group_by_app(&processes).into_iter().find(|g| g.root_pid == pid) {
+        Some(group) => group.processes,
+        None => {
+            let tree = ProcessTree::build(&processes);
+            std::iter::once(pid)
+                .chain(tree.descendants(pid))
+                .filter_map(|pid| tree.get(pid))
+                .cloned()
+                .collect()
+        }
+    };
+    if members.is_empty() {
+        return vec![(pid, terminate_with_grace(root, force, grace))];
+    }
 
-            // 如果需要强制终止
-            if force {
-                if let Some(process) = sys.process(sysinfo_pid) {
-                    return process.kill_with(Signal::Kill).unwrap_or(false);
-                }
-            }
+    let root_outcome = terminate_with_grace(root, force, grace);
+    let mut outcomes: Vec<(u32, Option<TerminationOutcome>)> = members.iter().map(|p| {
+        let outcome = if p.pid == pid {
+            Some(root_outcome)
+        } else if !process_alive(p.pid) {
+            // 根进程退出时辅助进程通常随之退出
+            Some(TerminationOutcome::ExitedAfterTerm)
+        } else if matches!(check_process_safety(p).level, SafetyLevel::Dangerous | SafetyLevel::Forbidden) {
+            Some(TerminationOutcome::StillAlive)
+        } else {
+            None
+        };
+        (p.pid, outcome)
+    }).collect();
+
+    let remaining: Vec<ProcessIdentity> = members.iter()
+        .zip(&outcomes)
+        .filter(|(_, (_, o))| o.is_none())
+        .map(|(p, _)| ProcessIdentity::of(p))
+        .collect();
+    for (member, outcome) in terminate_many(&remaining, force, grace) {
+        if let Some(entry) = outcomes.iter_mut().find(|(pid, _)| *pid == member) {
+            entry.1 = Some(outcome);
         }
     }
-    false
+    outcomes.into_iter().map(|(pid, o)| (pid, o.unwrap_or(TerminationOutcome::StillAlive))).collect()
 }
 
-
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::process::{self, Command};
+    use crate::rules::Rule;
 
     #[test]
     #[ignore] // This test can be slow and requires /usr/bin/purge to exist.
@@ -225,39 +557,200 @@ mod tests {
         }
     }
 
+    fn fake_procfs(name: &str) -> std::path::PathBuf {
+        let root = std::env::temp_dir().join(format!("rambo-procfs-{}-{}", name, process::id()));
+        std::fs::create_dir_all(root.join("sys/vm")).unwrap();
+        std::fs::write(root.join("sys/vm/drop_caches"), "").unwrap();
+        root
+    }
+
+    #[test]
+    fn can_drop_caches_in_fake_procfs() {
+        let root = fake_procfs("drop");
+
+        drop_caches(DropCachesMode::All, &root).unwrap();
+        let written = std::fs::read_to_string(root.join("sys/vm/drop_caches")).unwrap();
+        assert_eq!(written.trim(), "3");
+
+        drop_caches(DropCachesMode::PageCache, &root).unwrap();
+        let written = std::fs::read_to_string(root.join("sys/vm/drop_caches")).unwrap();
+        assert_eq!(written.trim(), "1");
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn drop_caches_without_procfs_entry_is_not_found() {
+        let root = std::env::temp_dir().join(format!("rambo-procfs-missing-{}", process::id()));
+        assert!(matches!(drop_caches(DropCachesMode::PageCache, &root), Err(PurgeError::CommandNotFound)));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn killer_boost_reports_each_round() {
+        use crate::stats::FakeStatsProvider;
+
+        let root = fake_procfs("killer");
+        let mut config = Config::default();
+        config.release.procfs_root = root.clone();
+
+        let sample = |free_mb| MemStats { total_mb: 8192, free_mb, ..Default::default() };
+        let provider = FakeStatsProvider::with_samples(vec![sample(1000), sample(1400), sample(1600)]);
+
+        let report = killer_boost_with(&provider, &mut ProcessMonitor::new(), &config).unwrap();
+        assert_eq!(report.rounds.len(), 3);
+        assert_eq!(report.rounds.iter().map(|r| r.round).collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert_eq!(report.rounds[0].step.delta_mb, 400);
+        // 默认配置不允许终止进程，第二轮被跳过
+        assert!(!report.termination_enabled);
+        assert_eq!(report.rounds[1].step.status, crate::strategy::StepStatus::Skipped);
+        assert_eq!(report.rounds[2].step.details["mode"], 3);
+        assert_eq!(report.delta_mb, 600);
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn drop_caches_mode_values() {
+        for value in 1..=3 {
+            assert_eq!(DropCachesMode::from_value(value).unwrap().value(), value);
+        }
+        assert!(DropCachesMode::from_value(0).is_none());
+    }
+
+    fn named_rules(rules: &[(&str, RuleAction)]) -> RuleSet {
+        let rules: Vec<Rule> = rules.iter().map(|&(name, action)| Rule::exact_name(name, action, name.to_string())).collect();
+        RuleSet::compile(&rules, MemoryMetric::Rss).unwrap()
+    }
+
     #[test]
     fn can_filter_candidates() {
-        let p1 = ProcessInfo { pid: 1, name: "good_process".to_string(), rss_mb: 600, is_frontmost: false, cmd: vec![], cpu_usage: 0.0 };
-        let p2 = ProcessInfo { pid: 2, name: "too_small".to_string(), rss_mb: 400, is_frontmost: false, cmd: vec![], cpu_usage: 0.0 };
-        let p3 = ProcessInfo { pid: 3, name: "frontmost".to_string(), rss_mb: 700, is_frontmost: true, cmd: vec![], cpu_usage: 0.0 };
-        let p4 = ProcessInfo { pid: 4, name: "blacklisted".to_string(), rss_mb: 800, is_frontmost: false, cmd: vec![], cpu_usage: 0.0 };
-        let p5 = ProcessInfo { pid: 5, name: "whitelisted".to_string(), rss_mb: 900, is_frontmost: false, cmd: vec![], cpu_usage: 0.0 };
+        let p1 = ProcessInfo { pid: 1, name: "good_process".to_string(), rss_mb: 600, is_frontmost: false, ..Default::default() };
+        let p2 = ProcessInfo { pid: 2, name: "too_small".to_string(), rss_mb: 400, is_frontmost: false, ..Default::default() };
+        let p3 = ProcessInfo { pid: 3, name: "frontmost".to_string(), rss_mb: 700, is_frontmost: true, ..Default::default() };
+        let p4 = ProcessInfo { pid: 4, name: "blacklisted".to_string(), rss_mb: 800, is_frontmost: false, ..Default::default() };
+        let p5 = ProcessInfo { pid: 5, name: "whitelisted".to_string(), rss_mb: 900, is_frontmost: false, ..Default::default() };
 
         let processes = vec![p1.clone(), p2.clone(), p3.clone(), p4.clone(), p5.clone()];
 
-        let mut blacklist = HashSet::new();
-        blacklist.insert("blacklisted".to_string());
-
-        let candidates = get_candidate_processes(&processes, 500, &HashSet::new(), &blacklist);
+        let rules = named_rules(&[("blacklisted", RuleAction::NeverSuggest)]);
+        let candidates = get_candidate_processes(&processes, 500, &rules);
         assert_eq!(candidates.len(), 2);
         assert!(candidates.iter().any(|p| p.pid == 1));
         assert!(candidates.iter().any(|p| p.pid == 5));
 
-        let mut whitelist = HashSet::new();
-        whitelist.insert("whitelisted".to_string());
-        let candidates = get_candidate_processes(&processes, 500, &whitelist, &blacklist);
+        // 白名单表示保护，而不是“只建议这些”
+        let config = Config {
+            whitelist_processes: vec!["whitelisted".to_string()],
+            blacklist_processes: vec!["blacklisted".to_string()],
+            ..Config::default()
+        };
+        let candidates = get_candidate_processes(&processes, 500, &RuleSet::from_config(&config).unwrap());
         assert_eq!(candidates.len(), 1);
-        assert_eq!(candidates[0].pid, 5);
+        assert_eq!(candidates[0].pid, 1);
+
+        // 按 USS 计算时共享页占比高的进程不再满足阈值
+        let shared = ProcessInfo { pid: 6, name: "electron_helper".to_string(), rss_mb: 800, uss_mb: Some(90), ..Default::default() };
+        let processes = vec![p1, shared];
+        let candidates = get_candidate_processes_by(&processes, &CandidateCriteria { metric: MemoryMetric::Uss, ..CandidateCriteria::rss(500) }, &rules);
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].pid, 1);
+    }
+
+    #[test]
+    fn idle_criterion_prefers_idle_hogs() {
+        let hog = |pid, rss_mb, idle_secs| ProcessInfo { pid, name: format!("hog{}", pid), rss_mb, idle_secs, ..Default::default() };
+        let processes = vec![
+            hog(1, 2000, Some(0)),      // 忙
+            hog(2, 800, Some(1800)),    // 空闲 30 分钟
+            hog(3, 1200, Some(900)),    // 空闲 15 分钟
+            hog(4, 3000, None),         // 没有 CPU 采样
+        ];
+
+        let any = get_candidate_processes_by(&processes, &CandidateCriteria::rss(500), &RuleSet::default());
+        assert_eq!(any.iter().map(|p| p.pid).collect::<Vec<_>>(), vec![2, 3, 4, 1]);
+
+        let criteria = CandidateCriteria { idle_for: Some(Duration::from_secs(10 * 60)), ..CandidateCriteria::rss(500) };
+        let idle = get_candidate_processes_by(&processes, &criteria, &RuleSet::default());
+        assert_eq!(idle.iter().map(|p| p.pid).collect::<Vec<_>>(), vec![2, 3]);
+
+        let mut config = Config::default();
+        config.idle.min_idle_minutes = 10;
+        assert_eq!(CandidateCriteria::from_config(&config).idle_for, Some(Duration::from_secs(600)));
+    }
+
+    #[test]
+    fn rules_rank_and_bypass_criteria() {
+        let hog = |pid, rss_mb, idle_secs| ProcessInfo { pid, name: format!("hog{}", pid), rss_mb, idle_secs, ..Default::default() };
+        let processes = vec![
+            hog(1, 2000, Some(1800)),
+            hog(2, 800, Some(0)),
+            hog(3, 100, None),
+            hog(4, 3000, None),
+        ];
+        let rules = named_rules(&[("hog2", RuleAction::Prefer), ("hog3", RuleAction::AutoTerminate), ("hog4", RuleAction::Protect)]);
+
+        // auto_terminate 的进程不到阈值也不空闲，仍排在最前；prefer 排在未匹配的进程之前
+        let criteria = CandidateCriteria { idle_for: Some(Duration::from_secs(60)), ..CandidateCriteria::rss(500) };
+        let candidates = get_candidate_processes_by(&processes, &criteria, &rules);
+        assert_eq!(candidates.iter().map(|p| p.pid).collect::<Vec<_>>(), vec![3, 1]);
+        let candidates = get_candidate_processes_by(&processes, &CandidateCriteria::rss(500), &rules);
+        assert_eq!(candidates.iter().map(|p| p.pid).collect::<Vec<_>>(), vec![3, 2, 1]);
+    }
+
+    #[test]
+    fn can_filter_candidate_groups() {
+        let app = |pid, name: &str, rss_mb, is_frontmost| ProcessInfo { pid, ppid: Some(1), name: name.to_string(), rss_mb, is_frontmost, ..Default::default() };
+        let helper = |pid, ppid, rss_mb| ProcessInfo { pid, ppid: Some(ppid), name: "Helper".to_string(), rss_mb, ..Default::default() };
+        let processes = vec![
+            app(1, "launchd", 10, false),
+            app(100, "Slack", 200, false),
+            helper(101, 100, 200),
+            helper(102, 100, 200),
+            app(200, "Editor", 300, true),
+            helper(201, 200, 400),
+            app(300, "Mail", 450, false),
+        ];
+        let groups = group_by_app(&processes);
+
+        // 单个进程都不到 500 MB，按应用汇总后 Slack 超过阈值
+        let candidates = get_candidate_groups(&groups, &CandidateCriteria::rss(500), &RuleSet::default());
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].name, "Slack");
+        assert_eq!(candidates[0].pids(), vec![100, 101, 102]);
+
+        let rules = named_rules(&[("Slack", RuleAction::NeverSuggest)]);
+        assert!(get_candidate_groups(&groups, &CandidateCriteria::rss(500), &rules).is_empty());
+
+        // 前台应用即使匹配 auto_terminate 也不会成为候选
+        let rules = named_rules(&[("Mail", RuleAction::AutoTerminate), ("Editor", RuleAction::AutoTerminate)]);
+        let candidates = get_candidate_groups(&groups, &CandidateCriteria::rss(500), &rules);
+        assert_eq!(candidates.iter().map(|g| g.name.as_str()).collect::<Vec<_>>(), vec!["Mail", "Slack"]);
+    }
+
+    #[test]
+    #[ignore] // Spawns and signals real processes; root-owned children are skipped by the safety check.
+    fn terminate_app_reports_every_member() {
+        let mut parent = Command::new("sh").arg("-c").arg("sleep 30 & wait").spawn().unwrap();
+        std::thread::sleep(Duration::from_millis(200));
+
+        let root = ProcessIdentity::current(parent.id()).unwrap();
+        let results = terminate_app(&root, true, Duration::from_millis(500));
+        let _ = parent.wait();
+        assert_eq!(results[0].0, parent.id());
+        assert!(results.len() >= 2, "expected the shell and its sleep child: {:?}", results);
+        assert!(results.iter().all(|&(_, outcome)| outcome.is_terminated()));
     }
 
     #[test]
     #[ignore] // This test is flaky and affects other processes.
     fn can_terminate() {
-        let child = Command::new("sleep").arg("10").spawn().unwrap();
+        let mut child = Command::new("sleep").arg("10").spawn().unwrap();
         let pid = child.id();
 
-        let terminated = terminate(pid, true);
-        assert!(terminated);
+        let outcome = terminate(&ProcessIdentity::current(pid).unwrap(), true);
+        assert!(outcome.is_terminated());
+        let _ = child.wait();
 
         let output = Command::new("ps").arg("-p").arg(pid.to_string()).output().unwrap();
         assert!(!String::from_utf8_lossy(&output.stdout).contains(&pid.to_string()));
@@ -0,0 +1,293 @@
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use crate::config::Config;
+use crate::processes::{MemoryMetric, ProcessInfo};
+use crate::tree::AppGroup;
+
+/// 规则匹配后对进程的处理
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleAction {
+    /// 不作为候选，`rb kill` 也拒绝终止
+    Protect,
+    /// 不作为候选，但可以手动终止
+    NeverSuggest,
+    /// 作为候选时排在前面
+    Prefer,
+    /// 不看内存阈值和空闲条件，每次清理（含 Daemon）都终止；仍需 enable_process_termination 和安全检查
+    AutoTerminate,
+}
+
+impl fmt::Display for RuleAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            RuleAction::Protect => "protect",
+            RuleAction::NeverSuggest => "never_suggest",
+            RuleAction::Prefer => "prefer",
+            RuleAction::AutoTerminate => "auto_terminate",
+        })
+    }
+}
+
+/// `config.toml` 中的一条 `[[rules]]`：给出的条件全部满足才匹配，多条规则按顺序取第一条匹配的
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Rule {
+    /// 展示用名称，缺省为 `rules[<序号>]`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    pub action: RuleAction,
+    /// 进程名的 glob（`*`、`?`）；按应用聚合时也匹配应用名
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// 进程名的正则表达式
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name_regex: Option<String>,
+    /// 命令行（参数以空格连接）包含的子串
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cmdline: Option<String>,
+    /// 用户名或 uid
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    /// 至少运行了这么久，例如 "2h"
+    #[serde(default, with = "humantime_serde", skip_serializing_if = "Option::is_none")]
+    pub min_age: Option<Duration>,
+    /// 至少空闲了这么久，例如 "30m"；只有长期持有的进程监视器（Daemon、`--idle-for`）才有空闲数据
+    #[serde(default, with = "humantime_serde", skip_serializing_if = "Option::is_none")]
+    pub min_idle: Option<Duration>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_memory_mb: Option<u64>,
+    /// min_memory_mb 使用的口径，缺省为 memory_metric
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metric: Option<MemoryMetric>,
+}
+
+impl Rule {
+    /// 按进程名精确匹配的规则，用于兼容 whitelist_processes/blacklist_processes
+    pub fn exact_name(name: &str, action: RuleAction, label: String) -> Self {
+        Self {
+            label: Some(label),
+            action,
+            name: None,
+            name_regex: Some(format!("^{}$", regex::escape(name))),
+            cmdline: None,
+            user: None,
+            min_age: None,
+            min_idle: None,
+            min_memory_mb: None,
+            metric: None,
+        }
+    }
+}
+
+fn glob_to_regex(glob: &str) -> String {
+    let pattern: String = glob.chars().map(|c| match c {
+        '*' => ".*".to_string(),
+        '?' => ".".to_string(),
+        c => regex::escape(&c.to_string()),
+    }).collect();
+    format!("^{}$", pattern)
+}
+
+/// 编译后的规则
+#[derive(Debug, Clone)]
+pub struct CompiledRule {
+    rule: Rule,
+    label: String,
+    name: Option<Regex>,
+    name_regex: Option<Regex>,
+    metric: MemoryMetric,
+}
+
+impl CompiledRule {
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    pub fn action(&self) -> RuleAction {
+        self.rule.action
+    }
+
+    /// names 为进程名（按应用聚合时还有应用名），其余条件用 process 判断
+    fn matches(&self, names: &[&str], process: &ProcessInfo, now: u64) -> bool {
+        let rule = &self.rule;
+        let name_matches = |re: &Option<Regex>| re.as_ref().is_none_or(|re| names.iter().any(|n| re.is_match(n)));
+        if !name_matches(&self.name) || !name_matches(&self.name_regex) {
+            return false;
+        }
+        if let Some(needle) = &rule.cmdline {
+            if !process.cmd.join(" ").contains(needle.as_str()) {
+                return false;
+            }
+        }
+        if let Some(user) = &rule.user {
+            let uid_matches = process.uid.is_some_and(|uid| uid.to_string() == *user);
+            if process.user.as_deref() != Some(user.as_str()) && !uid_matches {
+                return false;
+            }
+        }
+        if let Some(min_age) = rule.min_age {
+            // 启动时间未知时不满足
+            let old_enough = process.start_time.is_some_and(|start| now.saturating_sub(start) >= min_age.as_secs());
+            if !old_enough {
+                return false;
+            }
+        }
+        if let Some(min_idle) = rule.min_idle {
+            let idle_enough = process.idle_secs.is_some_and(|idle| idle >= min_idle.as_secs());
+            if !idle_enough {
+                return false;
+            }
+        }
+        if let Some(min_memory_mb) = rule.min_memory_mb {
+            if process.memory_mb(self.metric) < min_memory_mb {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// 编译好的规则列表
+#[derive(Debug, Clone, Default)]
+pub struct RuleSet {
+    rules: Vec<CompiledRule>,
+}
+
+impl RuleSet {
+    /// 编译规则；glob 或正则无效时返回错误
+    pub fn compile(rules: &[Rule], default_metric: MemoryMetric) -> Result<Self, String> {
+        let compile = |pattern: &str, i: usize| {
+            Regex::new(pattern).map_err(|e| format!("Invalid pattern in rules[{}]: {}", i, e))
+        };
+        let rules = rules.iter().enumerate().map(|(i, rule)| {
+            Ok(CompiledRule {
+                label: rule.label.clone().unwrap_or_else(|| format!("rules[{}]", i)),
+                name: rule.name.as_deref().map(|glob| compile(&glob_to_regex(glob), i)).transpose()?,
+                name_regex: rule.name_regex.as_deref().map(|re| compile(re, i)).transpose()?,
+                metric: rule.metric.unwrap_or(default_metric),
+                rule: rule.clone(),
+            })
+        }).collect::<Result<Vec<_>, String>>()?;
+        Ok(Self { rules })
+    }
+
+    /// 配置中的 `[[rules]]`，之后依次是由 whitelist_processes（protect）和 blacklist_processes（never_suggest）转换的规则
+    pub fn from_config(config: &Config) -> Result<Self, String> {
+        let legacy = config.whitelist_processes.iter()
+            .map(|name| Rule::exact_name(name, RuleAction::Protect, format!("whitelist_processes: {}", name)))
+            .chain(config.blacklist_processes.iter()
+                .map(|name| Rule::exact_name(name, RuleAction::NeverSuggest, format!("blacklist_processes: {}", name))));
+        let rules: Vec<Rule> = config.rules.iter().cloned().chain(legacy).collect();
+        Self::compile(&rules, config.memory_metric)
+    }
+
+    pub fn has_action(&self, action: RuleAction) -> bool {
+        self.rules.iter().any(|r| r.action() == action)
+    }
+
+    /// 第一条匹配该进程的规则
+    pub fn matching(&self, process: &ProcessInfo) -> Option<&CompiledRule> {
+        self.matching_at(&[&process.name], process, unix_now())
+    }
+
+    /// 第一条匹配该应用的规则：名称条件匹配应用名或根进程名，其余条件使用应用的汇总数据
+    pub fn matching_group(&self, group: &AppGroup) -> Option<&CompiledRule> {
+        let process = group.as_process();
+        self.matching_at(&[&group.name, &process.name], &process, unix_now())
+    }
+
+    fn matching_at(&self, names: &[&str], process: &ProcessInfo, now: u64) -> Option<&CompiledRule> {
+        self.rules.iter().find(|r| r.matches(names, process, now))
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: u64 = 1_700_000_000;
+
+    fn rules(toml_str: &str) -> RuleSet {
+        #[derive(Deserialize)]
+        struct Rules {
+            rules: Vec<Rule>,
+        }
+        let parsed: Rules = toml::from_str(toml_str).unwrap();
+        RuleSet::compile(&parsed.rules, MemoryMetric::Rss).unwrap()
+    }
+
+    fn matched<'a>(set: &'a RuleSet, process: &ProcessInfo) -> Option<&'a str> {
+        set.matching_at(&[&process.name], process, NOW).map(CompiledRule::label)
+    }
+
+    #[test]
+    fn matches_every_condition() {
+        let set = rules(r#"
+            [[rules]]
+            label = "databases"
+            name = "postgres*"
+            action = "protect"
+
+            [[rules]]
+            name_regex = "^(node|deno)$"
+            cmdline = "vite"
+            action = "prefer"
+
+            [[rules]]
+            user = "ci"
+            min_age = "2h"
+            min_memory_mb = 1000
+            action = "auto_terminate"
+
+            [[rules]]
+            min_idle = "30m"
+            metric = "uss"
+            min_memory_mb = 100
+            action = "never_suggest"
+        "#);
+        let process = |name: &str| ProcessInfo { name: name.to_string(), ..Default::default() };
+
+        assert_eq!(matched(&set, &process("postgres: writer")), Some("databases"));
+        assert_eq!(matched(&set, &process("mypostgres")), None);
+
+        let vite = ProcessInfo { cmd: vec!["node".to_string(), "node_modules/.bin/vite".to_string()], ..process("node") };
+        assert_eq!(matched(&set, &vite), Some("rules[1]"));
+        assert_eq!(matched(&set, &ProcessInfo { cmd: vec!["node".to_string(), "server.js".to_string()], ..process("node") }), None);
+
+        let job = ProcessInfo { user: Some("ci".to_string()), uid: Some(1001), start_time: Some(NOW - 3 * 3600), rss_mb: 2000, ..process("java") };
+        assert_eq!(matched(&set, &job), Some("rules[2]"));
+        assert_eq!(matched(&set, &ProcessInfo { user: None, ..job.clone() }), None);
+        assert_eq!(matched(&set, &ProcessInfo { start_time: Some(NOW - 60), ..job.clone() }), None);
+        assert_eq!(matched(&set, &ProcessInfo { start_time: None, ..job.clone() }), None);
+
+        let idle = ProcessInfo { idle_secs: Some(3600), rss_mb: 500, uss_mb: Some(50), ..process("idle") };
+        assert_eq!(matched(&set, &idle), None);
+        assert_eq!(matched(&set, &ProcessInfo { uss_mb: Some(200), ..idle }), Some("rules[3]"));
+    }
+
+    #[test]
+    fn maps_legacy_lists_and_rejects_bad_patterns() {
+        let config = Config {
+            whitelist_processes: vec!["Mail (beta)".to_string()],
+            blacklist_processes: vec!["Slack".to_string()],
+            rules: vec![Rule { label: Some("first".to_string()), ..Rule::exact_name("Slack", RuleAction::Prefer, String::new()) }],
+            ..Config::default()
+        };
+        let set = RuleSet::from_config(&config).unwrap();
+        let slack = ProcessInfo { name: "Slack".to_string(), ..Default::default() };
+        // 显式规则优先于旧的名单
+        assert_eq!(set.matching(&slack).map(CompiledRule::action), Some(RuleAction::Prefer));
+        let mail = set.matching(&ProcessInfo { name: "Mail (beta)".to_string(), ..Default::default() }).unwrap();
+        assert_eq!((mail.action(), mail.label()), (RuleAction::Protect, "whitelist_processes: Mail (beta)"));
+        assert!(set.matching(&ProcessInfo { name: "Mail".to_string(), ..Default::default() }).is_none());
+
+        let bad = Rule { name_regex: Some("(".to_string()), ..Rule::exact_name("x", RuleAction::Protect, String::new()) };
+        assert!(RuleSet::compile(&[bad], MemoryMetric::Rss).unwrap_err().contains("rules[0]"));
+    }
+}
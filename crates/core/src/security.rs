@@ -1,4 +1,6 @@
-use crate::processes::ProcessInfo;
+use crate::processes::{ProcessInfo, ProcessState};
+use crate::tree::AppGroup;
+use serde::{Deserialize, Serialize};
 
 /// System processes that should never be terminated
 const SYSTEM_PROCESSES: &[&str] = &[
@@ -42,7 +44,7 @@ const CRITICAL_PATTERNS: &[&str] = &[
     "WiFi",
 ];
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum SafetyLevel {
     Safe,
     Risky,
@@ -50,7 +52,7 @@ pub enum SafetyLevel {
     Forbidden,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SafetyCheck {
     pub level: SafetyLevel,
     pub reason: String,
@@ -109,6 +111,72 @@ pub fn check_process_safety(process: &ProcessInfo) -> SafetyCheck {
         };
     }
 
+    // Terminating our parent (usually the user's shell) would end the session
+    if process.pid == std::os::unix::process::parent_id() {
+        return SafetyCheck {
+            level: SafetyLevel::Forbidden,
+            reason: "Cannot terminate parent process".to_string(),
+            warnings,
+        };
+    }
+
+    // Zombies hold no memory; only reaping by their parent removes them
+    if process.state == ProcessState::Zombie {
+        return SafetyCheck {
+            level: SafetyLevel::Forbidden,
+            reason: "Zombie process holds no memory".to_string(),
+            warnings,
+        };
+    }
+
+    // Linux kernel threads are children of kthreadd (PID 2)
+    if cfg!(target_os = "linux") && process.ppid == Some(2) {
+        return SafetyCheck {
+            level: SafetyLevel::Forbidden,
+            reason: format!("Process '{}' is a kernel thread", process.name),
+            warnings,
+        };
+    }
+
+    if let Some(uid) = process.uid {
+        let user = process.user.clone().unwrap_or_else(|| uid.to_string());
+        if uid == 0 {
+            return SafetyCheck {
+                level: SafetyLevel::Dangerous,
+                reason: format!("Process '{}' is owned by root", process.name),
+                warnings,
+            };
+        }
+
+        let euid = unsafe { libc::geteuid() };
+        if uid != euid {
+            if euid != 0 {
+                return SafetyCheck {
+                    level: SafetyLevel::Forbidden,
+                    reason: format!("Process '{}' is owned by another user ({})", process.name, user),
+                    warnings,
+                };
+            }
+            warnings.push(format!("Process is owned by user {}", user));
+        }
+    }
+
+    // Recently started processes are likely still in use
+    if let Some(start_time) = process.start_time {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let age = now.saturating_sub(start_time);
+        if age < 60 {
+            warnings.push(format!("Process started {} seconds ago", age));
+        }
+    }
+
+    if let Some(swap_mb) = process.swap_mb.filter(|&mb| mb > 0) {
+        warnings.push(format!("{} MB already swapped out", swap_mb));
+    }
+
     // Process is frontmost (user is actively using it)
     if process.is_frontmost {
         warnings.push("Process is currently in the foreground".to_string());
@@ -148,6 +216,14 @@ pub fn filter_safe_processes(
         .collect()
 }
 
+/// 应用的所有成员都通过安全检查才保留
+pub fn filter_safe_groups(groups: &[AppGroup], allow_risky: bool) -> Vec<&AppGroup> {
+    groups
+        .iter()
+        .filter(|g| filter_safe_processes(&g.processes, allow_risky).len() == g.processes.len())
+        .collect()
+}
+
 pub fn require_confirmation(process: &ProcessInfo) -> bool {
     let safety = check_process_safety(process);
 
@@ -212,6 +288,7 @@ mod tests {
             rss_mb,
             cpu_usage: 0.0,
             is_frontmost,
+            ..Default::default()
         }
     }
 
@@ -250,6 +327,38 @@ mod tests {
         assert_eq!(safety.level, SafetyLevel::Safe);
     }
 
+    #[test]
+    fn test_process_metadata_checks() {
+        let euid = unsafe { libc::geteuid() };
+
+        let mut zombie = create_test_process("defunct_app", 1234, 0, false);
+        zombie.state = ProcessState::Zombie;
+        assert_eq!(check_process_safety(&zombie).level, SafetyLevel::Forbidden);
+
+        let mut root_owned = create_test_process("MyDaemon", 1234, 200, false);
+        root_owned.uid = Some(0);
+        assert_eq!(check_process_safety(&root_owned).level, SafetyLevel::Dangerous);
+
+        let mut parent = create_test_process("shell", std::os::unix::process::parent_id(), 10, false);
+        parent.uid = Some(euid);
+        assert_eq!(check_process_safety(&parent).level, SafetyLevel::Forbidden);
+
+        let mut own = create_test_process("MyApp", 1234, 200, false);
+        own.uid = Some(euid);
+        own.swap_mb = Some(300);
+        let safety = check_process_safety(&own);
+        if euid != 0 {
+            assert_eq!(safety.level, SafetyLevel::Safe);
+            assert!(safety.warnings.iter().any(|w| w.contains("swapped out")));
+        }
+
+        if euid != 0 {
+            let mut other = create_test_process("MyApp", 1234, 200, false);
+            other.uid = Some(euid + 1);
+            assert_eq!(check_process_safety(&other).level, SafetyLevel::Forbidden);
+        }
+    }
+
     #[test]
     fn test_filter_safe_processes() {
         let processes = vec![
@@ -0,0 +1,510 @@
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use sysinfo::System;
+use crate::config::Config;
+use crate::psi::{read_psi, PsiThresholds, DEFAULT_MEMORY_PSI_PATH};
+use crate::{derive_pressure, derive_pressure_level, MemStats, PressureLevel, SwapStats, BYTES_PER_MB};
+
+/// 内存统计数据源，Daemon、boost() 和 CLI 都通过它读取 `MemStats`
+pub trait MemoryStatsProvider: Send + Sync {
+    /// 后端名称，用于诊断输出
+    fn name(&self) -> &'static str;
+
+    fn read_mem_stats(&self) -> Result<MemStats, String>;
+}
+
+/// macOS 原生后端：mach `host_statistics64`
+#[cfg(target_os = "macos")]
+#[derive(Debug, Default)]
+pub struct MachStatsProvider {
+    swap_rates: SwapRateTracker,
+}
+
+#[cfg(target_os = "macos")]
+impl MemoryStatsProvider for MachStatsProvider {
+    fn name(&self) -> &'static str {
+        "mach"
+    }
+
+    fn read_mem_stats(&self) -> Result<MemStats, String> {
+        use std::mem;
+
+        unsafe {
+            #[allow(deprecated)]
+            let host_port = libc::mach_host_self();
+            if host_port == 0 { return Err("mach_host_self() returned 0".to_string()); }
+
+            let mut vm_stats: libc::vm_statistics64 = mem::zeroed();
+            let mut count = libc::HOST_VM_INFO64_COUNT;
+
+            let kern_return = libc::host_statistics64(
+                host_port,
+                libc::HOST_VM_INFO64,
+                &mut vm_stats as *mut _ as libc::host_info64_t,
+                &mut count,
+            );
+
+            if kern_return != libc::KERN_SUCCESS { return Err(format!("host_statistics64() failed with code {}", kern_return)); }
+
+            let page_size = libc::sysconf(libc::_SC_PAGESIZE) as u64;
+            if page_size == 0 { return Err("sysconf(_SC_PAGESIZE) returned 0".to_string()); }
+
+            let to_mb = |pages: u32| (pages as u64 * page_size) / BYTES_PER_MB;
+
+            let mut total_mem: u64 = 0;
+            let mut mib: [i32; 2] = [libc::CTL_HW, libc::HW_MEMSIZE];
+            let mut size = mem::size_of::<u64>();
+            if libc::sysctl(mib.as_mut_ptr(), 2, &mut total_mem as *mut _ as *mut libc::c_void, &mut size, std::ptr::null_mut(), 0) != 0 {
+                return Err("sysctl for HW_MEMSIZE failed".to_string());
+            }
+
+            // 没有交换文件时 vm.swapusage 为全 0
+            let mut swap_usage: libc::xsw_usage = mem::zeroed();
+            let mut size = mem::size_of::<libc::xsw_usage>();
+            let swap_ok = libc::sysctlbyname(c"vm.swapusage".as_ptr(), &mut swap_usage as *mut _ as *mut libc::c_void, &mut size, std::ptr::null_mut(), 0) == 0;
+            let rates = self.swap_rates.rates(vm_stats.swapins, vm_stats.swapouts, Instant::now());
+
+            let mut stats = MemStats {
+                total_mb: total_mem / BYTES_PER_MB,
+                free_mb: to_mb(vm_stats.free_count),
+                active_mb: to_mb(vm_stats.active_count),
+                inactive_mb: to_mb(vm_stats.inactive_count),
+                wired_mb: to_mb(vm_stats.wire_count),
+                compressed_mb: to_mb(vm_stats.compressor_page_count),
+                available_mb: to_mb(vm_stats.free_count) + to_mb(vm_stats.inactive_count),
+                psi: None,
+                swap: SwapStats {
+                    total_mb: if swap_ok { swap_usage.xsu_total / BYTES_PER_MB } else { 0 },
+                    used_mb: if swap_ok { swap_usage.xsu_used / BYTES_PER_MB } else { 0 },
+                    swap_in_per_sec: rates.map(|r| r.0),
+                    swap_out_per_sec: rates.map(|r| r.1),
+                },
+                pressure: PressureLevel::Normal,
+            };
+
+            stats.pressure = derive_pressure_level(&stats);
+            Ok(stats)
+        }
+    }
+}
+
+/// 跨平台后端：sysinfo，只提供 total/free
+pub struct SysinfoStatsProvider {
+    sys: Mutex<System>,
+}
+
+impl SysinfoStatsProvider {
+    pub fn new() -> Self {
+        Self { sys: Mutex::new(System::new()) }
+    }
+}
+
+impl Default for SysinfoStatsProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MemoryStatsProvider for SysinfoStatsProvider {
+    fn name(&self) -> &'static str {
+        "sysinfo"
+    }
+
+    fn read_mem_stats(&self) -> Result<MemStats, String> {
+        let mut sys = self.sys.lock().map_err(|_| "sysinfo state poisoned".to_string())?;
+        sys.refresh_memory();
+
+        // sysinfo 不提供换入/换出计数，只有用量
+        let mut stats = MemStats {
+            total_mb: sys.total_memory() / BYTES_PER_MB,
+            free_mb: sys.free_memory() / BYTES_PER_MB,
+            active_mb: 0, // Not available in sysinfo
+            inactive_mb: 0, // Not available in sysinfo
+            wired_mb: 0, // Not available in sysinfo
+            compressed_mb: 0, // Not available in sysinfo
+            available_mb: sys.available_memory() / BYTES_PER_MB,
+            psi: None,
+            swap: SwapStats {
+                total_mb: sys.total_swap() / BYTES_PER_MB,
+                used_mb: sys.used_swap() / BYTES_PER_MB,
+                ..Default::default()
+            },
+            pressure: PressureLevel::Normal,
+        };
+
+        stats.pressure = derive_pressure_level(&stats);
+        Ok(stats)
+    }
+}
+
+/// Linux 原生后端：直接解析 `/proc/meminfo`，并附带 `/proc/pressure/memory` 的 PSI 数据
+/// 和 `/proc/vmstat` 推算的换入/换出速率
+#[derive(Debug, Clone)]
+pub struct ProcMeminfoProvider {
+    path: PathBuf,
+    psi_path: Option<PathBuf>,
+    psi_thresholds: Option<PsiThresholds>,
+    vmstat_path: Option<PathBuf>,
+    swap_rates: SwapRateTracker,
+}
+
+impl ProcMeminfoProvider {
+    pub fn new() -> Self {
+        Self::with_path("/proc/meminfo")
+            .with_psi_path(DEFAULT_MEMORY_PSI_PATH)
+            .with_vmstat_path("/proc/vmstat")
+    }
+
+    /// 使用指定的 meminfo 文件（不读取 PSI 和 vmstat），便于针对采集的样本测试
+    pub fn with_path(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            psi_path: None,
+            psi_thresholds: None,
+            vmstat_path: None,
+            swap_rates: SwapRateTracker::default(),
+        }
+    }
+
+    pub fn with_vmstat_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.vmstat_path = Some(path.into());
+        self
+    }
+
+    pub fn with_psi_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.psi_path = Some(path.into());
+        self
+    }
+
+    /// 设置后由 PSI 推导压力等级；PSI 读取失败时仍使用比例启发式
+    pub fn with_psi_thresholds(mut self, thresholds: PsiThresholds) -> Self {
+        self.psi_thresholds = Some(thresholds);
+        self
+    }
+}
+
+impl Default for ProcMeminfoProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MemoryStatsProvider for ProcMeminfoProvider {
+    fn name(&self) -> &'static str {
+        "procfs"
+    }
+
+    fn read_mem_stats(&self) -> Result<MemStats, String> {
+        let mut stats = parse_meminfo(&self.path)?;
+        if let Some(psi_path) = &self.psi_path {
+            stats.psi = read_psi(psi_path).ok();
+            stats.pressure = derive_pressure(&stats, self.psi_thresholds.as_ref());
+        }
+        if let Some(vmstat_path) = &self.vmstat_path {
+            let counters = fs::read_to_string(vmstat_path).ok().as_deref().and_then(parse_vmstat_swap);
+            if let Some((swap_in, swap_out)) = counters {
+                let rates = self.swap_rates.rates(swap_in, swap_out, Instant::now());
+                stats.swap.swap_in_per_sec = rates.map(|r| r.0);
+                stats.swap.swap_out_per_sec = rates.map(|r| r.1);
+            }
+        }
+        Ok(stats)
+    }
+}
+
+/// 由累计换入/换出页数推算每秒速率；克隆共享同一份上次读数
+#[derive(Debug, Clone, Default)]
+pub struct SwapRateTracker {
+    last: Arc<Mutex<Option<(Instant, u64, u64)>>>,
+}
+
+impl SwapRateTracker {
+    /// 记录本次累计值并返回与上次之间的速率；首次调用或计数回绕时为 None
+    pub fn rates(&self, swap_in: u64, swap_out: u64, now: Instant) -> Option<(f64, f64)> {
+        let mut last = self.last.lock().ok()?;
+        let (at, prev_in, prev_out) = last.replace((now, swap_in, swap_out))?;
+        let secs = now.saturating_duration_since(at).as_secs_f64();
+        if secs <= 0.0 || swap_in < prev_in || swap_out < prev_out {
+            return None;
+        }
+        Some(((swap_in - prev_in) as f64 / secs, (swap_out - prev_out) as f64 / secs))
+    }
+}
+
+/// `/proc/vmstat` 中的累计换入/换出页数（pswpin/pswpout）
+pub fn parse_vmstat_swap(content: &str) -> Option<(u64, u64)> {
+    let field = |key: &str| content.lines()
+        .find_map(|line| line.strip_prefix(key)?.strip_prefix(' ')?.trim().parse().ok());
+    Some((field("pswpin")?, field("pswpout")?))
+}
+
+pub fn parse_meminfo(path: &Path) -> Result<MemStats, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    parse_meminfo_str(&content)
+}
+
+/// 将 meminfo 字段映射到 `MemStats`：Unevictable/Mlocked 计为 wired，Zswap 计为 compressed
+pub fn parse_meminfo_str(content: &str) -> Result<MemStats, String> {
+    let fields: HashMap<&str, u64> = content
+        .lines()
+        .filter_map(|line| {
+            let (key, rest) = line.split_once(':')?;
+            let kb = rest.split_whitespace().next()?.parse().ok()?;
+            Some((key.trim(), kb))
+        })
+        .collect();
+
+    let kb_to_mb = |key: &str| fields.get(key).map(|kb| kb / 1024).unwrap_or(0);
+
+    let total_mb = fields.get("MemTotal")
+        .map(|kb| kb / 1024)
+        .ok_or("meminfo is missing MemTotal")?;
+
+    let free_mb = kb_to_mb("MemFree");
+    let available_mb = if fields.contains_key("MemAvailable") {
+        kb_to_mb("MemAvailable")
+    } else {
+        // Kernels before 3.14 have no MemAvailable
+        free_mb + kb_to_mb("Inactive")
+    };
+
+    let mut stats = MemStats {
+        total_mb,
+        free_mb,
+        active_mb: kb_to_mb("Active"),
+        inactive_mb: kb_to_mb("Inactive"),
+        wired_mb: kb_to_mb("Unevictable").max(kb_to_mb("Mlocked")),
+        compressed_mb: kb_to_mb("Zswap"),
+        available_mb,
+        psi: None,
+        swap: SwapStats {
+            total_mb: kb_to_mb("SwapTotal"),
+            used_mb: kb_to_mb("SwapTotal").saturating_sub(kb_to_mb("SwapFree")),
+            ..Default::default()
+        },
+        pressure: PressureLevel::Normal,
+    };
+
+    stats.pressure = derive_pressure_level(&stats);
+    Ok(stats)
+}
+
+/// 测试/工具用后端：按顺序返回预设的样本，样本耗尽后重复最后一个
+#[derive(Debug, Default)]
+pub struct FakeStatsProvider {
+    samples: Mutex<VecDeque<MemStats>>,
+    last: Mutex<Option<MemStats>>,
+}
+
+impl FakeStatsProvider {
+    pub fn new(stats: MemStats) -> Self {
+        Self::with_samples(vec![stats])
+    }
+
+    pub fn with_samples(samples: Vec<MemStats>) -> Self {
+        Self {
+            samples: Mutex::new(samples.into()),
+            last: Mutex::new(None),
+        }
+    }
+
+    pub fn push(&self, stats: MemStats) {
+        self.samples.lock().unwrap().push_back(stats);
+    }
+}
+
+impl MemoryStatsProvider for FakeStatsProvider {
+    fn name(&self) -> &'static str {
+        "fake"
+    }
+
+    fn read_mem_stats(&self) -> Result<MemStats, String> {
+        let mut last = self.last.lock().unwrap();
+        if let Some(next) = self.samples.lock().unwrap().pop_front() {
+            *last = Some(next);
+        }
+        last.clone().ok_or_else(|| "fake provider has no samples".to_string())
+    }
+}
+
+/// 编译期默认后端：macOS 用 mach，Linux 用 procfs（启用 `use-sysinfo` 时均为 sysinfo）
+pub fn default_provider() -> Arc<dyn MemoryStatsProvider> {
+    #[cfg(all(target_os = "macos", not(feature = "use-sysinfo")))]
+    {
+        Arc::new(MachStatsProvider::default())
+    }
+
+    #[cfg(all(target_os = "linux", not(feature = "use-sysinfo")))]
+    {
+        Arc::new(ProcMeminfoProvider::new())
+    }
+
+    #[cfg(any(feature = "use-sysinfo", not(any(target_os = "macos", target_os = "linux"))))]
+    {
+        Arc::new(SysinfoStatsProvider::new())
+    }
+}
+
+/// 按名称在运行时选择后端（"auto"、"mach"、"procfs"、"sysinfo"）
+pub fn provider_by_name(name: &str) -> Result<Arc<dyn MemoryStatsProvider>, String> {
+    match name {
+        "auto" | "" => Ok(default_provider()),
+        #[cfg(target_os = "macos")]
+        "mach" => Ok(Arc::new(MachStatsProvider::default())),
+        #[cfg(target_os = "linux")]
+        "procfs" => Ok(Arc::new(ProcMeminfoProvider::new())),
+        "sysinfo" => Ok(Arc::new(SysinfoStatsProvider::new())),
+        other => Err(format!("Unknown or unsupported stats backend: {}", other)),
+    }
+}
+
+/// 根据配置创建后端，procfs 后端会带上配置中的 PSI 阈值
+pub fn provider_from_config(config: &Config) -> Result<Arc<dyn MemoryStatsProvider>, String> {
+    #[cfg(target_os = "linux")]
+    {
+        let backend = config.stats_backend.as_str();
+        let auto_procfs = matches!(backend, "auto" | "") && !cfg!(feature = "use-sysinfo");
+        if backend == "procfs" || auto_procfs {
+            let mut provider = ProcMeminfoProvider::new();
+            if let Some(thresholds) = config.pressure.psi_thresholds() {
+                provider = provider.with_psi_thresholds(thresholds);
+            }
+            return Ok(Arc::new(provider));
+        }
+    }
+
+    provider_by_name(&config.stats_backend)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(free_mb: u64) -> MemStats {
+        MemStats {
+            total_mb: 16384,
+            free_mb,
+            ..Default::default()
+        }
+    }
+
+    fn fixture(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(name)
+    }
+
+    #[test]
+    fn fake_provider_replays_samples() {
+        let provider = FakeStatsProvider::with_samples(vec![sample(1000), sample(2000)]);
+        assert_eq!(provider.read_mem_stats().unwrap().free_mb, 1000);
+        assert_eq!(provider.read_mem_stats().unwrap().free_mb, 2000);
+        // 样本耗尽后重复最后一个
+        assert_eq!(provider.read_mem_stats().unwrap().free_mb, 2000);
+
+        provider.push(sample(3000));
+        assert_eq!(provider.read_mem_stats().unwrap().free_mb, 3000);
+    }
+
+    #[test]
+    fn empty_fake_provider_errors() {
+        let provider = FakeStatsProvider::default();
+        assert!(provider.read_mem_stats().is_err());
+    }
+
+    #[test]
+    fn can_select_provider_by_name() {
+        assert_eq!(provider_by_name("sysinfo").unwrap().name(), "sysinfo");
+        assert!(provider_by_name("auto").is_ok());
+        assert!(provider_by_name("bogus").is_err());
+    }
+
+    #[test]
+    fn parses_meminfo_fixture() {
+        let stats = ProcMeminfoProvider::with_path(fixture("meminfo_workstation.txt"))
+            .read_mem_stats()
+            .unwrap();
+
+        assert_eq!(stats.total_mb, 31_862);
+        assert_eq!(stats.free_mb, 1_203);
+        assert_eq!(stats.available_mb, 12_640);
+        assert_eq!(stats.active_mb, 14_310);
+        assert_eq!(stats.inactive_mb, 9_821);
+        assert_eq!(stats.wired_mb, 64);
+        assert_eq!(stats.compressed_mb, 1_536);
+        assert_eq!(stats.swap.total_mb, 8_191);
+        assert_eq!(stats.swap.used_mb, 1_024);
+        assert_eq!(stats.swap.swap_in_per_sec, None);
+        assert_eq!(stats.pressure, PressureLevel::Normal);
+    }
+
+    #[test]
+    fn swap_rates_come_from_vmstat_deltas() {
+        assert_eq!(parse_vmstat_swap("pgpgin 10\npswpin 1200\npswpout 3400\n"), Some((1200, 3400)));
+        assert_eq!(parse_vmstat_swap("pgpgin 10\n"), None);
+
+        let tracker = SwapRateTracker::default();
+        let t0 = Instant::now();
+        assert_eq!(tracker.rates(1000, 500, t0), None);
+        assert_eq!(tracker.rates(3000, 600, t0 + std::time::Duration::from_secs(10)), Some((200.0, 10.0)));
+        // 计数回绕（例如重新挂载）时不报告速率
+        assert_eq!(tracker.rates(10, 10, t0 + std::time::Duration::from_secs(20)), None);
+
+        let vmstat = std::env::temp_dir().join(format!("rambo-vmstat-{}", std::process::id()));
+        fs::write(&vmstat, "pswpin 100\npswpout 100\n").unwrap();
+        let provider = ProcMeminfoProvider::with_path(fixture("meminfo_workstation.txt")).with_vmstat_path(&vmstat);
+        assert_eq!(provider.read_mem_stats().unwrap().swap.swap_in_per_sec, None);
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        fs::write(&vmstat, "pswpin 500\npswpout 100\n").unwrap();
+        let swap = provider.read_mem_stats().unwrap().swap;
+        assert!(swap.swap_in_per_sec.unwrap() > 0.0);
+        assert_eq!(swap.swap_out_per_sec, Some(0.0));
+        fs::remove_file(vmstat).unwrap();
+    }
+
+    #[test]
+    fn parses_meminfo_under_pressure() {
+        let stats = parse_meminfo(&fixture("meminfo_pressure.txt")).unwrap();
+
+        assert_eq!(stats.total_mb, 7_837);
+        assert_eq!(stats.available_mb, 301);
+        assert_eq!(stats.wired_mb, 0);
+        assert_eq!(stats.compressed_mb, 0); // No Zswap line
+        assert_eq!(stats.pressure, PressureLevel::Critical);
+    }
+
+    #[test]
+    fn psi_thresholds_drive_procfs_pressure() {
+        let provider = ProcMeminfoProvider::with_path(fixture("meminfo_workstation.txt"))
+            .with_psi_path(fixture("psi_memory_stalled.txt"));
+        let stats = provider.read_mem_stats().unwrap();
+        assert!(stats.psi.is_some());
+        // Without thresholds the ratio heuristics still apply
+        assert_eq!(stats.pressure, PressureLevel::Normal);
+
+        let provider = provider.with_psi_thresholds(PsiThresholds::default());
+        assert_eq!(provider.read_mem_stats().unwrap().pressure, PressureLevel::Critical);
+
+        // Missing PSI file falls back to ratios
+        let provider = ProcMeminfoProvider::with_path(fixture("meminfo_workstation.txt"))
+            .with_psi_path(fixture("does_not_exist.txt"))
+            .with_psi_thresholds(PsiThresholds::default());
+        let stats = provider.read_mem_stats().unwrap();
+        assert!(stats.psi.is_none());
+        assert_eq!(stats.pressure, PressureLevel::Normal);
+    }
+
+    #[test]
+    fn meminfo_without_total_is_rejected() {
+        assert!(parse_meminfo_str("MemFree: 1024 kB\n").is_err());
+        assert!(parse_meminfo(&fixture("does_not_exist.txt")).is_err());
+    }
+
+    #[test]
+    fn sysinfo_provider_reads_stats() {
+        let stats = SysinfoStatsProvider::new().read_mem_stats().unwrap();
+        assert!(stats.total_mb > 0);
+    }
+}
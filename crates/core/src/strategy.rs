@@ -0,0 +1,1055 @@
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use crate::config::{Config, ReleaseConfig};
+use crate::processes::{MemoryMetric, ProcessInfo, ProcessMonitor};
+use crate::release::{
+    drop_caches_with_permission, get_candidate_groups, get_candidate_processes_by, purge_with_permission,
+    terminate_app,
+    BoostError, BoostResult, CandidateCriteria, DropCachesMode, PurgeError, ReleaseMethod,
+};
+use crate::security::{check_process_safety, filter_safe_groups, filter_safe_processes, SafetyCheck, SafetyLevel};
+use crate::tree::{group_by_app, AppGroup, ProcessGrouping};
+use crate::measure::{measure_reclaim, SettleWindow};
+use crate::stats::MemoryStatsProvider;
+use crate::budget::BudgetSelection;
+use crate::relaunch::{RelaunchEntry, RelaunchRegistry};
+use crate::rules::{CompiledRule, RuleAction, RuleSet};
+use crate::termination::{terminate_many, ProcessIdentity};
+use crate::MemStats;
+
+/// 一种内存释放手段，boost() 按顺序执行一组策略
+pub trait ReleaseStrategy: Send + Sync {
+    fn name(&self) -> &str;
+
+    /// 执行前检查，返回错误时该步骤被跳过
+    fn preconditions(&self) -> Result<(), BoostError>;
+
+    /// 执行释放，返回该步骤的细节（写入日志和 JSON 输出）
+    fn execute(&self) -> Result<Value, BoostError>;
+
+    /// 基于执行前的内存统计粗略估计可释放的内存（MB）
+    fn estimated_effect_mb(&self, before: &MemStats) -> u64;
+
+    /// 属于缓存释放的策略返回其释放方式
+    fn release_method(&self) -> Option<ReleaseMethod> {
+        None
+    }
+
+    /// 只产生报告、不释放内存的策略（如进程建议）返回 false，
+    /// 流水线至少要有一个释放内存的步骤成功才算 boost 成功
+    fn releases_memory(&self) -> bool {
+        true
+    }
+
+    /// 执行时会被发送信号的进程
+    fn targets(&self) -> &[ProcessInfo] {
+        &[]
+    }
+}
+
+/// 清理强度，每个等级对应 `[boost]` 配置中的一组动作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BoostLevel {
+    Low,
+    #[default]
+    Medium,
+    High,
+}
+
+impl BoostLevel {
+    pub fn description(&self) -> &'static str {
+        match self {
+            BoostLevel::Low => "轻度清理 - 释放系统缓存",
+            BoostLevel::Medium => "中度清理 - 释放缓存并给出进程建议",
+            BoostLevel::High => "强力清理 - 释放缓存并终止安全的候选进程",
+        }
+    }
+
+    pub fn icon(&self) -> &'static str {
+        match self {
+            BoostLevel::Low => "🟡",
+            BoostLevel::Medium => "🟠",
+            BoostLevel::High => "🔴",
+        }
+    }
+
+    pub fn next(&self) -> BoostLevel {
+        match self {
+            BoostLevel::Low => BoostLevel::Medium,
+            BoostLevel::Medium => BoostLevel::High,
+            BoostLevel::High => BoostLevel::Low,
+        }
+    }
+
+    pub fn prev(&self) -> BoostLevel {
+        match self {
+            BoostLevel::High => BoostLevel::Medium,
+            BoostLevel::Medium => BoostLevel::Low,
+            BoostLevel::Low => BoostLevel::High,
+        }
+    }
+}
+
+impl fmt::Display for BoostLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            BoostLevel::Low => "low",
+            BoostLevel::Medium => "medium",
+            BoostLevel::High => "high",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for BoostLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "low" => Ok(BoostLevel::Low),
+            "medium" => Ok(BoostLevel::Medium),
+            "high" => Ok(BoostLevel::High),
+            _ => Err(format!("Invalid boost level '{}': expected low, medium or high", s)),
+        }
+    }
+}
+
+/// 清理等级可以组合的动作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BoostAction {
+    /// 平台缓存释放：macOS purge / Linux drop_caches
+    #[serde(alias = "purge", alias = "drop_caches")]
+    ReleaseCaches,
+    /// 列出可终止的候选进程，但不终止
+    Suggest,
+    /// 终止通过安全检查的候选进程（需要 enable_process_termination）
+    TerminateCandidates,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepStatus {
+    Completed,
+    Skipped,
+    Failed,
+}
+
+/// 流水线中单个策略的执行记录
+#[derive(Debug, Clone, Serialize)]
+pub struct BoostStep {
+    pub name: String,
+    pub status: StepStatus,
+    pub before: MemStats,
+    pub after: MemStats,
+    pub delta_mb: i64,
+    pub estimated_mb: u64,
+    #[serde(with = "humantime_serde")]
+    pub duration: Duration,
+    pub details: Value,
+    pub error: Option<String>,
+}
+
+/// macOS: /usr/sbin/purge
+pub struct PurgeStrategy {
+    pub request_permission: bool,
+}
+
+impl ReleaseStrategy for PurgeStrategy {
+    fn name(&self) -> &str {
+        "purge"
+    }
+
+    fn preconditions(&self) -> Result<(), BoostError> {
+        if std::path::Path::new("/usr/sbin/purge").exists() {
+            Ok(())
+        } else {
+            Err(BoostError::Purge(PurgeError::CommandNotFound))
+        }
+    }
+
+    fn execute(&self) -> Result<Value, BoostError> {
+        let (duration, status) = purge_with_permission(self.request_permission).map_err(BoostError::Purge)?;
+        Ok(json!({ "exit_code": status.code(), "purge_ms": duration.as_millis() as u64 }))
+    }
+
+    fn estimated_effect_mb(&self, before: &MemStats) -> u64 {
+        // purge 主要回收文件缓存，大致对应一半的 inactive 页
+        before.inactive_mb / 2
+    }
+
+    fn release_method(&self) -> Option<ReleaseMethod> {
+        Some(ReleaseMethod::Purge)
+    }
+}
+
+/// Linux: sync + /proc/sys/vm/drop_caches
+pub struct DropCachesStrategy {
+    pub mode: DropCachesMode,
+    pub procfs_root: PathBuf,
+    pub request_permission: bool,
+}
+
+impl ReleaseStrategy for DropCachesStrategy {
+    fn name(&self) -> &str {
+        "drop_caches"
+    }
+
+    fn preconditions(&self) -> Result<(), BoostError> {
+        if self.procfs_root.join("sys/vm/drop_caches").exists() {
+            Ok(())
+        } else {
+            Err(BoostError::Purge(PurgeError::CommandNotFound))
+        }
+    }
+
+    fn execute(&self) -> Result<Value, BoostError> {
+        drop_caches_with_permission(self.mode, &self.procfs_root, self.request_permission).map_err(BoostError::Purge)?;
+        Ok(json!({ "mode": self.mode.value() }))
+    }
+
+    fn estimated_effect_mb(&self, before: &MemStats) -> u64 {
+        match self.mode {
+            DropCachesMode::Slab => 0,
+            DropCachesMode::PageCache | DropCachesMode::All => before.inactive_mb / 2,
+        }
+    }
+
+    fn release_method(&self) -> Option<ReleaseMethod> {
+        Some(ReleaseMethod::DropCaches { mode: self.mode })
+    }
+}
+
+/// 只报告候选进程，不释放内存
+pub struct SuggestStrategy {
+    pub candidates: Vec<ProcessInfo>,
+}
+
+impl ReleaseStrategy for SuggestStrategy {
+    fn name(&self) -> &str {
+        "suggest"
+    }
+
+    fn preconditions(&self) -> Result<(), BoostError> {
+        Ok(())
+    }
+
+    fn execute(&self) -> Result<Value, BoostError> {
+        let results: Vec<Value> = self.candidates.iter().map(|p| {
+            json!({ "pid": p.pid, "name": p.name, "rss_mb": p.rss_mb })
+        }).collect();
+        Ok(json!({ "processes": results }))
+    }
+
+    fn estimated_effect_mb(&self, _before: &MemStats) -> u64 {
+        0
+    }
+
+    fn releases_memory(&self) -> bool {
+        false
+    }
+}
+
+/// 终止给定的进程（调用方负责候选筛选和安全检查）
+pub struct TerminateStrategy {
+    pub targets: Vec<ProcessInfo>,
+    pub force: bool,
+    /// 对应配置中的 enable_process_termination
+    pub enabled: bool,
+    /// App 时每个目标是应用根进程，连同辅助进程一起终止
+    pub grouping: ProcessGrouping,
+    /// SIGTERM 后等待退出的时间，对应 release.terminate_grace_ms
+    pub grace: Duration,
+    /// 记录被终止进程的重启信息，None 表示不记录
+    pub relaunch: Option<RelaunchRegistry>,
+}
+
+impl ReleaseStrategy for TerminateStrategy {
+    fn name(&self) -> &str {
+        "terminate"
+    }
+
+    fn preconditions(&self) -> Result<(), BoostError> {
+        if !self.enabled {
+            Err(BoostError::Precondition("process termination is disabled (enable_process_termination = false)".to_string()))
+        } else if self.targets.is_empty() {
+            Err(BoostError::Precondition("no processes selected for termination".to_string()))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn execute(&self) -> Result<Value, BoostError> {
+        // 命令行、工作目录和环境变量必须在进程退出前读取
+        let captured: Vec<RelaunchEntry> = match &self.relaunch {
+            Some(registry) => self.targets.iter().map(|p| registry.capture(p)).collect(),
+            None => Vec::new(),
+        };
+        // 每个目标的报告，以及目标本身（按应用终止时为根进程）是否已退出
+        let mut results: Vec<(Value, bool)> = match self.grouping {
+            // 容器应通过其运行时停止，按 cgroup 聚合时仍逐个终止进程；所有目标共用一个宽限期
+            ProcessGrouping::Process | ProcessGrouping::Cgroup => {
+                // 目标是选定时的进程表快照，身份不符（PID 已被复用）的不会收到信号
+                let identities: Vec<ProcessIdentity> = self.targets.iter().map(ProcessIdentity::of).collect();
+                self.targets.iter().zip(terminate_many(&identities, self.force, self.grace)).map(|(p, (_, outcome))| {
+                    let report = json!({ "pid": p.pid, "name": p.name, "rss_mb": p.rss_mb, "terminated": outcome.is_terminated(), "outcome": outcome });
+                    (report, outcome.is_terminated())
+                }).collect()
+            }
+            ProcessGrouping::App => self.targets.iter().map(|p| {
+                let members = terminate_app(&ProcessIdentity::of(p), self.force, self.grace);
+                let terminated = members.iter().all(|&(_, outcome)| outcome.is_terminated());
+                let root_terminated = members.iter().any(|&(pid, outcome)| pid == p.pid && outcome.is_terminated());
+                let members: Vec<Value> = members.iter().map(|&(pid, outcome)| {
+                    json!({ "pid": pid, "terminated": outcome.is_terminated(), "outcome": outcome })
+                }).collect();
+                (json!({ "pid": p.pid, "name": p.name, "rss_mb": p.rss_mb, "terminated": terminated, "members": members }), root_terminated)
+            }).collect(),
+        };
+
+        let mut relaunch_error = None;
+        if let Some(registry) = &self.relaunch {
+            let (recorded, entries): (Vec<usize>, Vec<RelaunchEntry>) = captured.into_iter()
+                .enumerate()
+                .filter(|&(i, _)| results[i].1)
+                .unzip();
+            match registry.record(entries) {
+                Ok(ids) => {
+                    for (i, id) in recorded.into_iter().zip(ids) {
+                        results[i].0["relaunch_id"] = json!(id);
+                    }
+                }
+                // 记录失败不影响终止结果
+                Err(e) => relaunch_error = Some(e),
+            }
+        }
+
+        let results: Vec<Value> = results.into_iter().map(|(report, _)| report).collect();
+        if let Some(error) = relaunch_error {
+            return Ok(json!({ "processes": results, "relaunch_error": error }));
+        }
+        Ok(json!({ "processes": results }))
+    }
+
+    fn estimated_effect_mb(&self, _before: &MemStats) -> u64 {
+        // 终止进程只能释放独占页，有 USS 时按 USS 估算
+        self.targets.iter().map(|p| p.memory_mb(MemoryMetric::Uss)).sum()
+    }
+
+    fn targets(&self) -> &[ProcessInfo] {
+        &self.targets
+    }
+}
+
+/// 平台默认的缓存释放策略：macOS 用 purge，Linux 用 drop_caches
+pub fn default_cache_strategy(config: &ReleaseConfig) -> Box<dyn ReleaseStrategy> {
+    if cfg!(target_os = "linux") {
+        Box::new(DropCachesStrategy {
+            mode: config.drop_caches_mode,
+            procfs_root: config.procfs_root.clone(),
+            request_permission: false,
+        })
+    } else {
+        Box::new(PurgeStrategy { request_permission: false })
+    }
+}
+
+/// 计划对应的清理模式
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PlanKind {
+    Level { level: BoostLevel },
+    Killer,
+}
+
+/// 计划中会被终止的进程及其安全检查结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlannedTarget {
+    pub process: ProcessInfo,
+    pub safety: SafetyCheck,
+}
+
+/// 计划中的一个策略
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlannedStrategy {
+    pub name: String,
+    pub method: Option<ReleaseMethod>,
+    /// 前置条件是否满足；不满足时执行阶段会跳过
+    pub ready: bool,
+    pub precondition: Option<String>,
+    pub estimated_mb: u64,
+    pub targets: Vec<PlannedTarget>,
+}
+
+/// boost 执行前的可审阅计划，可以保存为 JSON 之后再执行
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BoostPlan {
+    pub kind: PlanKind,
+    pub created_at: DateTime<Utc>,
+    pub before: MemStats,
+    pub strategies: Vec<PlannedStrategy>,
+    pub estimated_reclaim_mb: u64,
+}
+
+impl BoostPlan {
+    /// 所有会被发送信号的 PID
+    pub fn signalled_pids(&self) -> Vec<u32> {
+        self.strategies.iter().flat_map(|s| s.targets.iter().map(|t| t.process.pid)).collect()
+    }
+}
+
+/// 执行保存的计划时被剔除的目标进程
+#[derive(Debug, Clone, Serialize)]
+pub struct RejectedTarget {
+    pub pid: u32,
+    pub name: String,
+    pub reason: String,
+}
+
+/// 用当前进程表重新校验计划中的目标：进程必须仍然存在、名称一致、未被规则保护且仍然通过安全检查
+pub fn revalidate_targets(targets: &[PlannedTarget], current: &[ProcessInfo], rules: &RuleSet) -> (Vec<ProcessInfo>, Vec<RejectedTarget>) {
+    let mut accepted = Vec::new();
+    let mut rejected = Vec::new();
+
+    for target in targets {
+        let planned = &target.process;
+        let reject = |reason: String| RejectedTarget { pid: planned.pid, name: planned.name.clone(), reason };
+
+        let Some(process) = current.iter().find(|p| p.pid == planned.pid) else {
+            rejected.push(reject("process no longer exists".to_string()));
+            continue;
+        };
+        if process.name != planned.name || !ProcessIdentity::of(planned).matches(&ProcessIdentity::of(process)) {
+            rejected.push(reject(format!("PID now belongs to '{}'", process.name)));
+            continue;
+        }
+        if let Some(rule) = rules.matching(process).filter(|r| r.action() == RuleAction::Protect) {
+            rejected.push(reject(format!("protected by rule '{}'", rule.label())));
+            continue;
+        }
+        let safety = check_process_safety(process);
+        if safety.level != SafetyLevel::Safe {
+            rejected.push(reject(format!("safety check is now {:?}: {}", safety.level, safety.reason)));
+            continue;
+        }
+        accepted.push(process.clone());
+    }
+
+    (accepted, rejected)
+}
+
+/// 深度缓存清理：Linux 释放页缓存和 slab，macOS 再执行一次 purge
+pub fn deep_cache_strategy(config: &ReleaseConfig) -> Box<dyn ReleaseStrategy> {
+    if cfg!(target_os = "linux") {
+        Box::new(DropCachesStrategy {
+            mode: DropCachesMode::All,
+            procfs_root: config.procfs_root.clone(),
+            request_permission: false,
+        })
+    } else {
+        Box::new(PurgeStrategy { request_permission: false })
+    }
+}
+
+/// 按配置的阈值、规则和安全检查筛选出的候选进程；按应用聚合时返回代表各应用的根进程
+pub fn boost_candidates(config: &Config, processes: &[ProcessInfo]) -> Vec<ProcessInfo> {
+    boost_candidates_by(config, &CandidateCriteria::from_config(config), processes)
+}
+
+/// 同 [`boost_candidates`]，但使用给定的筛选条件
+pub fn boost_candidates_by(config: &Config, criteria: &CandidateCriteria, processes: &[ProcessInfo]) -> Vec<ProcessInfo> {
+    safe_candidates(config, criteria, processes, None)
+}
+
+/// 匹配 auto_terminate 规则且通过安全检查的候选
+pub fn auto_terminate_targets(config: &Config, processes: &[ProcessInfo]) -> Vec<ProcessInfo> {
+    safe_candidates(config, &CandidateCriteria::from_config(config), processes, Some(RuleAction::AutoTerminate))
+}
+
+/// 规则无法编译时不返回候选；only 限定匹配规则的动作
+fn safe_candidates(config: &Config, criteria: &CandidateCriteria, processes: &[ProcessInfo], only: Option<RuleAction>) -> Vec<ProcessInfo> {
+    let Ok(rules) = RuleSet::from_config(config) else {
+        return Vec::new();
+    };
+    let wanted = |rule: Option<&CompiledRule>| only.is_none() || rule.map(CompiledRule::action) == only;
+
+    if config.group_by == ProcessGrouping::App {
+        let groups = group_by_app(processes);
+        let candidates: Vec<AppGroup> = get_candidate_groups(&groups, criteria, &rules)
+            .into_iter()
+            .filter(|g| wanted(rules.matching_group(g)))
+            .cloned()
+            .collect();
+        return filter_safe_groups(&candidates, false).into_iter().map(AppGroup::as_process).collect();
+    }
+
+    let candidates: Vec<ProcessInfo> = get_candidate_processes_by(processes, criteria, &rules)
+        .into_iter()
+        .filter(|p| wanted(rules.matching(p)))
+        .cloned()
+        .collect();
+    filter_safe_processes(&candidates, false).into_iter().cloned().collect()
+}
+
+/// 按顺序执行的策略列表，记录每一步前后的内存统计和耗时
+#[derive(Default)]
+pub struct BoostPipeline {
+    strategies: Vec<Box<dyn ReleaseStrategy>>,
+    /// 全部策略执行后的采样窗口，None 表示只读取一次
+    settle: Option<SettleWindow>,
+}
+
+impl BoostPipeline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 只包含平台默认缓存释放的流水线，即原来的 boost()
+    pub fn for_config(config: &ReleaseConfig) -> Self {
+        let mut pipeline = Self::new();
+        pipeline.push(default_cache_strategy(config));
+        pipeline.settle = SettleWindow::from_config(config);
+        pipeline
+    }
+
+    /// 按 `[boost]` 中该等级配置的动作构建流水线；只有需要时才刷新进程表
+    pub fn for_level(config: &Config, level: BoostLevel, monitor: &mut ProcessMonitor) -> Self {
+        let actions = config.boost.actions(level);
+        let mut candidates = None;
+        let mut pipeline = Self::new();
+        pipeline.settle = SettleWindow::from_config(&config.release);
+
+        for action in actions {
+            match action {
+                BoostAction::ReleaseCaches => pipeline.push(default_cache_strategy(&config.release)),
+                BoostAction::Suggest => pipeline.push(Box::new(SuggestStrategy {
+                    candidates: candidates.get_or_insert_with(|| boost_candidates(config, &monitor.refresh())).clone(),
+                })),
+                BoostAction::TerminateCandidates => {
+                    let targets = if config.enable_process_termination {
+                        candidates.get_or_insert_with(|| boost_candidates(config, &monitor.refresh())).clone()
+                    } else {
+                        Vec::new()
+                    };
+                    pipeline.push(Box::new(TerminateStrategy {
+                        targets,
+                        force: false,
+                        enabled: config.enable_process_termination,
+                        grouping: config.group_by,
+                        grace: config.release.terminate_grace(),
+                        relaunch: RelaunchRegistry::from_config(&config.relaunch),
+                    }));
+                }
+            }
+        }
+
+        // 没有终止步骤的等级也终止匹配 auto_terminate 规则的进程；没有这类规则时不刷新进程表
+        let has_auto_rules = RuleSet::from_config(config).is_ok_and(|rules| rules.has_action(RuleAction::AutoTerminate));
+        if config.enable_process_termination && has_auto_rules && !actions.contains(&BoostAction::TerminateCandidates) {
+            let targets = auto_terminate_targets(config, &monitor.refresh());
+            if !targets.is_empty() {
+                pipeline.push(Box::new(TerminateStrategy {
+                    targets,
+                    force: false,
+                    enabled: true,
+                    grouping: config.group_by,
+                    grace: config.release.terminate_grace(),
+                    relaunch: RelaunchRegistry::from_config(&config.relaunch),
+                }));
+            }
+        }
+        pipeline
+    }
+
+    /// Killer 模式的三轮：标准缓存释放 → 终止候选进程 → 深度缓存清理
+    pub fn killer(config: &Config, monitor: &mut ProcessMonitor) -> Self {
+        let targets = if config.enable_process_termination {
+            boost_candidates(config, &monitor.refresh())
+        } else {
+            Vec::new()
+        };
+
+        let mut pipeline = Self::new();
+        pipeline.settle = SettleWindow::from_config(&config.release);
+        pipeline.push(default_cache_strategy(&config.release));
+        pipeline.push(Box::new(TerminateStrategy {
+            targets,
+            force: false,
+            enabled: config.enable_process_termination,
+            grouping: config.group_by,
+            grace: config.release.terminate_grace(),
+            relaunch: RelaunchRegistry::from_config(&config.relaunch),
+        }));
+        pipeline.push(deep_cache_strategy(&config.release));
+        pipeline
+    }
+
+    /// 终止按内存预算选出的进程
+    pub fn for_budget(config: &Config, selection: &BudgetSelection) -> Self {
+        let mut pipeline = Self::new();
+        pipeline.settle = SettleWindow::from_config(&config.release);
+        pipeline.push(Box::new(TerminateStrategy {
+            targets: selection.chosen.clone(),
+            force: false,
+            enabled: config.enable_process_termination,
+            grouping: config.group_by,
+            grace: config.release.terminate_grace(),
+            relaunch: RelaunchRegistry::from_config(&config.relaunch),
+        }));
+        pipeline
+    }
+
+    /// 从保存的计划重建流水线，终止目标按当前进程表重新校验
+    pub fn from_plan(plan: &BoostPlan, config: &Config, current: &[ProcessInfo]) -> Result<(Self, Vec<RejectedTarget>), BoostError> {
+        let mut pipeline = Self::new();
+        pipeline.settle = SettleWindow::from_config(&config.release);
+        let mut rejected = Vec::new();
+
+        for planned in &plan.strategies {
+            match (planned.name.as_str(), planned.method) {
+                ("purge", _) => pipeline.push(Box::new(PurgeStrategy { request_permission: false })),
+                ("drop_caches", Some(ReleaseMethod::DropCaches { mode })) => pipeline.push(Box::new(DropCachesStrategy {
+                    mode,
+                    procfs_root: config.release.procfs_root.clone(),
+                    request_permission: false,
+                })),
+                ("suggest", _) => pipeline.push(Box::new(SuggestStrategy { candidates: boost_candidates(config, current) })),
+                ("terminate", _) => {
+                    let rules = RuleSet::from_config(config).map_err(BoostError::Precondition)?;
+                    let (targets, mut dropped) = revalidate_targets(&planned.targets, current, &rules);
+                    rejected.append(&mut dropped);
+                    pipeline.push(Box::new(TerminateStrategy {
+                        targets,
+                        force: false,
+                        enabled: config.enable_process_termination,
+                        grouping: config.group_by,
+                        grace: config.release.terminate_grace(),
+                        relaunch: RelaunchRegistry::from_config(&config.relaunch),
+                    }));
+                }
+                (name, _) => return Err(BoostError::Precondition(format!("Unknown strategy '{}' in plan", name))),
+            }
+        }
+
+        Ok((pipeline, rejected))
+    }
+
+    /// 只检查前置条件并估算效果，不执行任何策略
+    pub fn plan(&self, kind: PlanKind, provider: &dyn MemoryStatsProvider) -> Result<BoostPlan, BoostError> {
+        let before = provider.read_mem_stats().map_err(BoostError::Stats)?;
+
+        let strategies: Vec<PlannedStrategy> = self.strategies.iter().map(|strategy| {
+            let precondition = strategy.preconditions().err().map(|e| format!("{:?}", e));
+            PlannedStrategy {
+                name: strategy.name().to_string(),
+                method: strategy.release_method(),
+                ready: precondition.is_none(),
+                precondition,
+                estimated_mb: strategy.estimated_effect_mb(&before),
+                targets: strategy.targets().iter().map(|p| PlannedTarget {
+                    process: p.clone(),
+                    safety: check_process_safety(p),
+                }).collect(),
+            }
+        }).collect();
+
+        let estimated_reclaim_mb = strategies.iter().filter(|s| s.ready).map(|s| s.estimated_mb).sum();
+
+        Ok(BoostPlan {
+            kind,
+            created_at: Utc::now(),
+            before,
+            strategies,
+            estimated_reclaim_mb,
+        })
+    }
+
+    pub fn push(&mut self, strategy: Box<dyn ReleaseStrategy>) {
+        self.strategies.push(strategy);
+    }
+
+    pub fn with_strategy(mut self, strategy: impl ReleaseStrategy + 'static) -> Self {
+        self.push(Box::new(strategy));
+        self
+    }
+
+    pub fn with_settle_window(mut self, settle: Option<SettleWindow>) -> Self {
+        self.settle = settle;
+        self
+    }
+
+    pub fn strategies(&self) -> &[Box<dyn ReleaseStrategy>] {
+        &self.strategies
+    }
+
+    /// 依次执行所有策略。只要有一步成功就返回 Ok，失败/跳过的步骤记录在 steps 中；
+    /// 没有任何一步成功时返回第一个错误
+    pub fn run(&self, provider: &dyn MemoryStatsProvider) -> Result<BoostResult, BoostError> {
+        let start = Instant::now();
+        let before = provider.read_mem_stats().map_err(BoostError::Stats)?;
+
+        let mut current = before.clone();
+        let mut steps = Vec::with_capacity(self.strategies.len());
+        let mut first_error = None;
+        let mut method = None;
+
+        for strategy in &self.strategies {
+            let estimated_mb = strategy.estimated_effect_mb(&current);
+
+            if let Err(e) = strategy.preconditions() {
+                steps.push(BoostStep {
+                    name: strategy.name().to_string(),
+                    status: StepStatus::Skipped,
+                    before: current.clone(),
+                    after: current.clone(),
+                    delta_mb: 0,
+                    estimated_mb,
+                    duration: Duration::ZERO,
+                    details: Value::Null,
+                    error: Some(format!("{:?}", e)),
+                });
+                first_error.get_or_insert(e);
+                continue;
+            }
+
+            let step_start = Instant::now();
+            let outcome = strategy.execute();
+            let duration = step_start.elapsed();
+            let after = provider.read_mem_stats();
+
+            let (status, details, mut error) = match outcome {
+                Ok(details) => {
+                    if method.is_none() {
+                        method = strategy.release_method();
+                    }
+                    (StepStatus::Completed, details, None)
+                }
+                Err(e) => {
+                    let message = format!("{:?}", e);
+                    first_error.get_or_insert(e);
+                    (StepStatus::Failed, Value::Null, Some(message))
+                }
+            };
+            // 读不到内存数据时步骤本身的结果仍然有效，按内存未变化记录后继续
+            let after = after.unwrap_or_else(|e| {
+                error.get_or_insert_with(|| format!("{:?}", BoostError::Stats(e)));
+                current.clone()
+            });
+
+            steps.push(BoostStep {
+                name: strategy.name().to_string(),
+                status,
+                delta_mb: after.free_mb as i64 - current.free_mb as i64,
+                before: current,
+                after: after.clone(),
+                estimated_mb,
+                duration,
+                details,
+                error,
+            });
+            current = after;
+        }
+
+        let released = steps.iter().zip(&self.strategies)
+            .any(|(step, strategy)| step.status == StepStatus::Completed && strategy.releases_memory());
+        if !released {
+            return Err(first_error.unwrap_or(BoostError::Precondition("no release strategies configured".to_string())));
+        }
+
+        let measurement = match self.settle {
+            Some(settle) => {
+                let (measurement, last) = measure_reclaim(provider, &before, settle).map_err(BoostError::Stats)?;
+                current = last;
+                Some(measurement)
+            }
+            None => None,
+        };
+
+        Ok(BoostResult {
+            delta_mb: current.free_mb as i64 - before.free_mb as i64,
+            before,
+            after: current,
+            duration: start.elapsed(),
+            method,
+            steps,
+            measurement,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::FakeStatsProvider;
+    use crate::rules::Rule;
+
+    struct FixedStrategy {
+        name: &'static str,
+        ready: bool,
+        succeed: bool,
+    }
+
+    impl ReleaseStrategy for FixedStrategy {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn preconditions(&self) -> Result<(), BoostError> {
+            if self.ready { Ok(()) } else { Err(BoostError::Precondition("not ready".to_string())) }
+        }
+
+        fn execute(&self) -> Result<Value, BoostError> {
+            if self.succeed { Ok(json!({ "ok": true })) } else { Err(BoostError::Stats("boom".to_string())) }
+        }
+
+        fn estimated_effect_mb(&self, _before: &MemStats) -> u64 {
+            100
+        }
+    }
+
+    fn stats(free_mb: u64) -> MemStats {
+        MemStats { total_mb: 16384, free_mb, ..Default::default() }
+    }
+
+    #[test]
+    fn pipeline_records_each_step() {
+        let provider = FakeStatsProvider::with_samples(vec![stats(1000), stats(1500), stats(1800)]);
+        let pipeline = BoostPipeline::new()
+            .with_strategy(FixedStrategy { name: "first", ready: true, succeed: true })
+            .with_strategy(FixedStrategy { name: "skipped", ready: false, succeed: true })
+            .with_strategy(FixedStrategy { name: "second", ready: true, succeed: true });
+
+        let result = pipeline.run(&provider).unwrap();
+        assert_eq!(result.delta_mb, 800);
+        assert_eq!(result.steps.len(), 3);
+
+        assert_eq!(result.steps[0].status, StepStatus::Completed);
+        assert_eq!(result.steps[0].delta_mb, 500);
+        assert_eq!(result.steps[1].status, StepStatus::Skipped);
+        assert_eq!(result.steps[1].before.free_mb, 1500);
+        assert_eq!(result.steps[2].before.free_mb, 1500);
+        assert_eq!(result.steps[2].after.free_mb, 1800);
+        assert_eq!(result.steps[2].estimated_mb, 100);
+    }
+
+    #[test]
+    fn pipeline_samples_settle_window() {
+        let provider = FakeStatsProvider::with_samples(vec![stats(1000), stats(1200), stats(1900), stats(1700)]);
+        let pipeline = BoostPipeline::new()
+            .with_strategy(FixedStrategy { name: "cache", ready: true, succeed: true })
+            .with_settle_window(Some(SettleWindow { window: Duration::from_millis(20), samples: 2 }));
+
+        let result = pipeline.run(&provider).unwrap();
+        assert_eq!(result.steps[0].delta_mb, 200);
+        let measurement = result.measurement.unwrap();
+        assert_eq!(measurement.peak_free_delta_mb, 900);
+        assert_eq!(measurement.final_free_delta_mb, 700);
+        assert_eq!(result.after.free_mb, 1700);
+        assert_eq!(result.delta_mb, 700);
+    }
+
+    #[test]
+    fn pipeline_keeps_going_after_failure() {
+        let provider = FakeStatsProvider::with_samples(vec![stats(1000), stats(1000), stats(1200)]);
+        let pipeline = BoostPipeline::new()
+            .with_strategy(FixedStrategy { name: "broken", ready: true, succeed: false })
+            .with_strategy(FixedStrategy { name: "working", ready: true, succeed: true });
+
+        let result = pipeline.run(&provider).unwrap();
+        assert_eq!(result.steps[0].status, StepStatus::Failed);
+        assert!(result.steps[0].error.is_some());
+        assert_eq!(result.steps[1].status, StepStatus::Completed);
+        assert_eq!(result.delta_mb, 200);
+    }
+
+    #[test]
+    fn pipeline_keeps_going_when_stats_read_fails() {
+        struct FailingRead(std::sync::atomic::AtomicU64);
+
+        impl MemoryStatsProvider for FailingRead {
+            fn name(&self) -> &'static str {
+                "failing"
+            }
+
+            fn read_mem_stats(&self) -> Result<MemStats, String> {
+                // 第二次读取（第一个步骤之后）失败
+                match self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst) {
+                    1 => Err("read failed".to_string()),
+                    n => Ok(stats(1000 + 100 * n)),
+                }
+            }
+        }
+
+        let pipeline = BoostPipeline::new()
+            .with_strategy(FixedStrategy { name: "first", ready: true, succeed: true })
+            .with_strategy(FixedStrategy { name: "second", ready: true, succeed: true });
+
+        let result = pipeline.run(&FailingRead(Default::default())).unwrap();
+        assert_eq!(result.steps[0].status, StepStatus::Completed);
+        assert!(result.steps[0].error.as_deref().unwrap().contains("read failed"));
+        assert_eq!((result.steps[0].after.free_mb, result.steps[0].delta_mb), (1000, 0));
+        assert_eq!(result.steps[1].after.free_mb, 1200);
+        assert_eq!(result.delta_mb, 200);
+    }
+
+    #[test]
+    fn pipeline_without_successful_steps_returns_first_error() {
+        let provider = FakeStatsProvider::new(stats(1000));
+        let pipeline = BoostPipeline::new()
+            .with_strategy(FixedStrategy { name: "skipped", ready: false, succeed: true })
+            .with_strategy(FixedStrategy { name: "broken", ready: true, succeed: false });
+
+        assert!(matches!(pipeline.run(&provider), Err(BoostError::Precondition(_))));
+        assert!(BoostPipeline::new().run(&provider).is_err());
+    }
+
+    #[test]
+    fn terminate_strategy_requires_targets() {
+        let strategy = TerminateStrategy {
+            targets: vec![], force: false, enabled: true, grouping: ProcessGrouping::Process, grace: Duration::ZERO, relaunch: None,
+        };
+        assert!(strategy.preconditions().is_err());
+        assert_eq!(strategy.estimated_effect_mb(&stats(0)), 0);
+    }
+
+    #[test]
+    fn suggestions_alone_do_not_count_as_success() {
+        let provider = FakeStatsProvider::new(stats(1000));
+        let pipeline = BoostPipeline::new()
+            .with_strategy(FixedStrategy { name: "skipped", ready: false, succeed: true })
+            .with_strategy(SuggestStrategy { candidates: vec![] });
+
+        assert!(matches!(pipeline.run(&provider), Err(BoostError::Precondition(_))));
+    }
+
+    #[test]
+    fn killer_pipeline_has_three_rounds() {
+        let config = Config::default();
+        let pipeline = BoostPipeline::killer(&config, &mut ProcessMonitor::new());
+        let names: Vec<&str> = pipeline.strategies().iter().map(|s| s.name()).collect();
+
+        assert_eq!(names.len(), 3);
+        assert_eq!(names[1], "terminate");
+        assert_eq!(names[0], names[2]);
+        assert!(pipeline.strategies()[1].preconditions().is_err());
+    }
+
+    fn process(pid: u32, name: &str, rss_mb: u64) -> ProcessInfo {
+        ProcessInfo { pid, name: name.to_string(), rss_mb, ..Default::default() }
+    }
+
+    #[test]
+    fn plan_lists_targets_and_estimates() {
+        let provider = FakeStatsProvider::new(MemStats { inactive_mb: 2000, ..stats(1000) });
+        let pipeline = BoostPipeline::new()
+            .with_strategy(FixedStrategy { name: "cache", ready: true, succeed: true })
+            .with_strategy(FixedStrategy { name: "blocked", ready: false, succeed: true })
+            .with_strategy(TerminateStrategy {
+                targets: vec![process(4242, "chrome_helper", 800)],
+                force: false,
+                enabled: true,
+                grouping: ProcessGrouping::Process,
+                grace: Duration::ZERO,
+                relaunch: None,
+            });
+
+        let plan = pipeline.plan(PlanKind::Level { level: BoostLevel::High }, &provider).unwrap();
+        assert_eq!(plan.strategies.len(), 3);
+        assert!(!plan.strategies[1].ready);
+        assert_eq!(plan.signalled_pids(), vec![4242]);
+        assert_eq!(plan.strategies[2].targets[0].safety.level, SafetyLevel::Safe);
+        // 不满足前置条件的策略不计入估算
+        assert_eq!(plan.estimated_reclaim_mb, 100 + 800);
+
+        let json = serde_json::to_string(&plan).unwrap();
+        let restored: BoostPlan = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.kind, plan.kind);
+        assert_eq!(restored.signalled_pids(), vec![4242]);
+    }
+
+    #[test]
+    fn revalidation_drops_changed_targets() {
+        let planned = |p: ProcessInfo| PlannedTarget { safety: check_process_safety(&p), process: p };
+        let targets = vec![
+            planned(process(2001, "editor", 600)),
+            planned(process(2002, "gone", 600)),
+            planned(process(2003, "old_name", 600)),
+            planned(ProcessInfo { start_time: Some(1_700_000_000), ..process(2004, "worker", 600) }),
+            planned(process(2005, "postgres", 600)),
+        ];
+        let current = vec![
+            process(2001, "editor", 650),
+            process(2003, "sshd", 20),
+            // 同名但启动时间不同：PID 已被复用
+            ProcessInfo { start_time: Some(1_700_000_900), ..process(2004, "worker", 600) },
+            process(2005, "postgres", 600),
+        ];
+        // 计划生成后新增的保护规则同样生效
+        let config = Config { whitelist_processes: vec!["postgres".to_string()], ..Config::default() };
+
+        let (accepted, rejected) = revalidate_targets(&targets, &current, &RuleSet::from_config(&config).unwrap());
+        assert_eq!(accepted.len(), 1);
+        assert_eq!(accepted[0].rss_mb, 650);
+        assert_eq!(rejected.iter().map(|r| r.pid).collect::<Vec<_>>(), vec![2002, 2003, 2004, 2005]);
+        assert_eq!(rejected[3].reason, "protected by rule 'whitelist_processes: postgres'");
+    }
+
+    #[test]
+    fn from_plan_rejects_unknown_strategies() {
+        let provider = FakeStatsProvider::new(stats(1000));
+        let mut plan = BoostPipeline::new()
+            .with_strategy(FixedStrategy { name: "cache", ready: true, succeed: true })
+            .plan(PlanKind::Killer, &provider)
+            .unwrap();
+        assert!(BoostPipeline::from_plan(&plan, &Config::default(), &[]).is_err());
+
+        plan.strategies[0].name = "suggest".to_string();
+        let (pipeline, rejected) = BoostPipeline::from_plan(&plan, &Config::default(), &[]).unwrap();
+        assert_eq!(pipeline.strategies()[0].name(), "suggest");
+        assert!(rejected.is_empty());
+    }
+
+    #[test]
+    fn can_parse_boost_level() {
+        assert_eq!("low".parse::<BoostLevel>(), Ok(BoostLevel::Low));
+        assert_eq!("HIGH".parse::<BoostLevel>(), Ok(BoostLevel::High));
+        assert!("extreme".parse::<BoostLevel>().is_err());
+        assert_eq!(BoostLevel::Medium.to_string(), "medium");
+        assert_eq!(BoostLevel::default(), BoostLevel::Medium);
+    }
+
+    #[test]
+    fn levels_map_to_configured_actions() {
+        let mut config = Config::default();
+        config.boost.low = vec![BoostAction::ReleaseCaches];
+        config.boost.high = vec![BoostAction::ReleaseCaches, BoostAction::TerminateCandidates];
+
+        let names = |level| -> Vec<String> {
+            BoostPipeline::for_level(&config, level, &mut ProcessMonitor::new()).strategies().iter().map(|s| s.name().to_string()).collect()
+        };
+        let cache_step = default_cache_strategy(&config.release).name().to_string();
+
+        assert_eq!(names(BoostLevel::Low), vec![cache_step.clone()]);
+        assert_eq!(names(BoostLevel::High), vec![cache_step, "terminate".to_string()]);
+
+        // 未开启进程终止时 High 的终止步骤会被跳过，不会扫描或终止任何进程
+        let high = BoostPipeline::for_level(&config, BoostLevel::High, &mut ProcessMonitor::new());
+        assert!(high.strategies()[1].preconditions().is_err());
+
+        // 开启终止但没有 auto_terminate 规则时，Low 不会刷新进程表
+        config.enable_process_termination = true;
+        let mut monitor = ProcessMonitor::new();
+        monitor.refresh();
+        assert_eq!(BoostPipeline::for_level(&config, BoostLevel::Low, &mut monitor).strategies().len(), 1);
+        assert!(!monitor.cpu_ready());
+        config.rules = vec![Rule::exact_name("no-such-process", RuleAction::AutoTerminate, String::new())];
+        BoostPipeline::for_level(&config, BoostLevel::Low, &mut monitor);
+        assert!(monitor.cpu_ready());
+    }
+}
@@ -0,0 +1,354 @@
+use std::io;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+use crate::processes::ProcessInfo;
+
+/// 未从配置读取时 SIGTERM 后等待进程退出的时间
+pub const DEFAULT_GRACE: Duration = Duration::from_secs(2);
+/// SIGKILL 后等待内核回收进程的时间
+const KILL_WAIT: Duration = Duration::from_millis(500);
+/// 阻塞等待时的轮询间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// 终止一个进程的结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TerminationOutcome {
+    /// SIGTERM 后在宽限期内退出
+    ExitedAfterTerm,
+    /// 宽限期后被 SIGKILL 终止
+    Killed,
+    /// 无权向该进程发送信号
+    PermissionDenied,
+    /// 进程不存在（发送信号前已退出）
+    NotFound,
+    /// 宽限期后仍在运行（未要求强制终止，或 SIGKILL 后仍未退出）
+    StillAlive,
+    /// PID 已被其他进程复用，未发送信号
+    IdentityChanged,
+}
+
+impl TerminationOutcome {
+    /// 进程是否已经不在了
+    pub fn is_terminated(self) -> bool {
+        matches!(self, TerminationOutcome::ExitedAfterTerm | TerminationOutcome::Killed)
+    }
+}
+
+impl std::fmt::Display for TerminationOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TerminationOutcome::ExitedAfterTerm => write!(f, "exited after SIGTERM"),
+            TerminationOutcome::Killed => write!(f, "killed with SIGKILL"),
+            TerminationOutcome::PermissionDenied => write!(f, "permission denied"),
+            TerminationOutcome::NotFound => write!(f, "not found"),
+            TerminationOutcome::StillAlive => write!(f, "still alive"),
+            TerminationOutcome::IdentityChanged => write!(f, "PID now belongs to a different process"),
+        }
+    }
+}
+
+/// 选定进程时记录的身份，发送信号前用来确认 PID 没有被复用；未知的字段不参与比较
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProcessIdentity {
+    pub pid: u32,
+    /// 启动时间（Unix 时间戳，秒）
+    pub start_time: Option<u64>,
+    pub exe: Option<PathBuf>,
+}
+
+impl ProcessIdentity {
+    pub fn of(process: &ProcessInfo) -> Self {
+        Self { pid: process.pid, start_time: process.start_time, exe: process.exe.clone() }
+    }
+
+    /// 读取 pid 当前对应进程的身份；进程不存在时返回 None
+    pub fn current(pid: u32) -> Option<Self> {
+        read_identity(pid)
+    }
+
+    /// 是否为同一个进程。不同进程后端换算启动时间时可能差一秒，因此允许 1 秒误差
+    pub fn matches(&self, other: &ProcessIdentity) -> bool {
+        let start_matches = match (self.start_time, other.start_time) {
+            (Some(a), Some(b)) => a.abs_diff(b) <= 1,
+            _ => true,
+        };
+        let exe_matches = match (&self.exe, &other.exe) {
+            (Some(a), Some(b)) => a == b,
+            _ => true,
+        };
+        self.pid == other.pid && start_matches && exe_matches
+    }
+
+    /// 确认 PID 仍属于该进程
+    pub fn verify(&self) -> Result<(), TerminationOutcome> {
+        match Self::current(self.pid) {
+            None => Err(TerminationOutcome::NotFound),
+            Some(current) if !self.matches(&current) => Err(TerminationOutcome::IdentityChanged),
+            Some(_) => Ok(()),
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn read_identity(pid: u32) -> Option<ProcessIdentity> {
+    use crate::procfs::{clock_ticks_per_second, parse_boot_time, parse_stat};
+
+    let dir = PathBuf::from("/proc").join(pid.to_string());
+    let stat = parse_stat(&std::fs::read_to_string(dir.join("stat")).ok()?).ok()?;
+    let boot_time = std::fs::read_to_string("/proc/stat").ok().as_deref().and_then(parse_boot_time);
+    Some(ProcessIdentity {
+        pid,
+        start_time: boot_time.map(|boot| boot + (stat.start_ticks as f64 / clock_ticks_per_second()) as u64),
+        exe: std::fs::read_link(dir.join("exe")).ok(),
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_identity(pid: u32) -> Option<ProcessIdentity> {
+    use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
+
+    let pid_key = Pid::from_u32(pid);
+    let mut sys = System::new();
+    sys.refresh_processes_specifics(
+        ProcessesToUpdate::Some(&[pid_key]),
+        true,
+        ProcessRefreshKind::nothing().with_exe(UpdateKind::Always),
+    );
+    let process = sys.process(pid_key)?;
+    Some(ProcessIdentity {
+        pid,
+        start_time: Some(process.start_time()).filter(|&t| t > 0),
+        exe: process.exe().map(|p| p.to_path_buf()),
+    })
+}
+
+/// 发送信号；pid 0 和超出 pid_t 范围的值会被解释为进程组，一律视为不存在
+fn send_signal(pid: u32, signal: libc::c_int) -> Result<(), TerminationOutcome> {
+    if pid == 0 || pid > i32::MAX as u32 {
+        return Err(TerminationOutcome::NotFound);
+    }
+    if unsafe { libc::kill(pid as libc::pid_t, signal) } == 0 {
+        return Ok(());
+    }
+    match io::Error::last_os_error().raw_os_error() {
+        Some(libc::EPERM) => Err(TerminationOutcome::PermissionDenied),
+        _ => Err(TerminationOutcome::NotFound),
+    }
+}
+
+/// 进程是否仍在运行；已退出但未被回收的僵尸进程视为已退出
+pub fn process_alive(pid: u32) -> bool {
+    match send_signal(pid, 0) {
+        Ok(()) | Err(TerminationOutcome::PermissionDenied) => !is_zombie(pid),
+        Err(_) => false,
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn is_zombie(pid: u32) -> bool {
+    std::fs::read_to_string(format!("/proc/{}/stat", pid))
+        .ok()
+        .and_then(|content| crate::procfs::parse_stat(&content).ok())
+        .is_some_and(|stat| stat.state == 'Z')
+}
+
+#[cfg(target_os = "macos")]
+fn is_zombie(pid: u32) -> bool {
+    // PROC_PIDTBSDINFO 对僵尸进程同样有效
+    let mut info: libc::proc_bsdinfo = unsafe { std::mem::zeroed() };
+    let size = std::mem::size_of::<libc::proc_bsdinfo>() as libc::c_int;
+    let written = unsafe {
+        libc::proc_pidinfo(pid as libc::c_int, libc::PROC_PIDTBSDINFO, 0, &mut info as *mut _ as *mut libc::c_void, size)
+    };
+    written == size && info.pbi_status == libc::SZOMB
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn is_zombie(_pid: u32) -> bool {
+    false
+}
+
+/// 进行中的终止：创建时发送 SIGTERM，之后由调用方轮询，可同时推进多个进程
+#[derive(Debug)]
+pub struct PendingTermination {
+    pid: u32,
+    identity: ProcessIdentity,
+    force: bool,
+    deadline: Instant,
+    killed: bool,
+    outcome: Option<TerminationOutcome>,
+}
+
+impl PendingTermination {
+    /// 确认身份后发送 SIGTERM；身份不符时不发送任何信号
+    pub fn start(identity: &ProcessIdentity, force: bool, grace: Duration) -> Self {
+        Self::start_at(identity, force, grace, Instant::now())
+    }
+
+    fn start_at(identity: &ProcessIdentity, force: bool, grace: Duration, now: Instant) -> Self {
+        Self {
+            pid: identity.pid,
+            identity: identity.clone(),
+            force,
+            deadline: now + grace,
+            killed: false,
+            outcome: identity.verify().and_then(|()| send_signal(identity.pid, libc::SIGTERM)).err(),
+        }
+    }
+
+    pub fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    /// 已有结果时返回结果；宽限期结束仍未退出时按 force 发送 SIGKILL 或报告 StillAlive
+    pub fn poll(&mut self, now: Instant) -> Option<TerminationOutcome> {
+        if self.outcome.is_some() {
+            return self.outcome;
+        }
+        let exited = if self.killed { TerminationOutcome::Killed } else { TerminationOutcome::ExitedAfterTerm };
+        if !process_alive(self.pid) {
+            self.outcome = Some(exited);
+        } else if now >= self.deadline {
+            // 宽限期内进程可能已退出且 PID 被复用，此时既不能再发信号，也不算仍在运行
+            self.outcome = match self.identity.verify() {
+                Err(_) => Some(exited),
+                Ok(()) if self.force && !self.killed => match send_signal(self.pid, libc::SIGKILL) {
+                    Ok(()) => {
+                        self.killed = true;
+                        self.deadline = now + KILL_WAIT;
+                        None
+                    }
+                    // 两次检查之间退出
+                    Err(TerminationOutcome::NotFound) => Some(exited),
+                    Err(outcome) => Some(outcome),
+                },
+                Ok(()) => Some(TerminationOutcome::StillAlive),
+            };
+        }
+        self.outcome
+    }
+
+    /// 距下一次需要处理（宽限期结束）的时间
+    pub fn time_left(&self, now: Instant) -> Duration {
+        self.deadline.saturating_duration_since(now)
+    }
+}
+
+/// 同时终止多个进程：先全部发送 SIGTERM，再共同等待宽限期，总耗时约为一个宽限期
+pub fn terminate_many(targets: &[ProcessIdentity], force: bool, grace: Duration) -> Vec<(u32, TerminationOutcome)> {
+    let mut pending: Vec<PendingTermination> = targets.iter().map(|target| PendingTermination::start(target, force, grace)).collect();
+    loop {
+        let now = Instant::now();
+        let mut next = POLL_INTERVAL;
+        let mut done = true;
+        for termination in pending.iter_mut() {
+            if termination.poll(now).is_none() {
+                done = false;
+                next = next.min(termination.time_left(now).max(Duration::from_millis(1)));
+            }
+        }
+        if done {
+            return pending.iter_mut().map(|t| (t.pid, t.poll(now).unwrap_or(TerminationOutcome::StillAlive))).collect();
+        }
+        std::thread::sleep(next);
+    }
+}
+
+/// 终止单个进程并等待结果
+pub fn terminate_with_grace(target: &ProcessIdentity, force: bool, grace: Duration) -> TerminationOutcome {
+    terminate_many(std::slice::from_ref(target), force, grace)[0].1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::{Child, Command};
+
+    fn argv0(pid: u32) -> Option<String> {
+        use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
+
+        let mut sys = System::new();
+        sys.refresh_processes_specifics(ProcessesToUpdate::Some(&[Pid::from_u32(pid)]), true, ProcessRefreshKind::nothing().with_cmd(UpdateKind::Always));
+        Some(sys.process(Pid::from_u32(pid))?.cmd().first()?.to_string_lossy().into_owned())
+    }
+
+    fn spawn(script: &str) -> (Child, ProcessIdentity) {
+        let child = Command::new("sh").arg("-c").arg(script).spawn().unwrap();
+        // 等 shell 设置好信号处理并 exec 成 sleep；sh 和 sleep 可能是同一个多合一程序（busybox），所以比较 argv[0] 而不是 exe
+        let deadline = Instant::now() + Duration::from_secs(5);
+        loop {
+            let argv0 = argv0(child.id());
+            if argv0.as_deref().is_some_and(|arg| std::path::Path::new(arg).ends_with("sleep")) {
+                break;
+            }
+            assert!(Instant::now() < deadline, "child never exec'd sleep: {:?}", argv0);
+            std::thread::sleep(POLL_INTERVAL);
+        }
+        let identity = ProcessIdentity::current(child.id()).unwrap();
+        (child, identity)
+    }
+
+    #[test]
+    fn reports_each_outcome() {
+        let (mut polite, polite_id) = spawn("exec sleep 30");
+        let (mut stubborn, stubborn_id) = spawn("trap '' TERM; exec sleep 30");
+        let (mut killable, killable_id) = spawn("trap '' TERM; exec sleep 30");
+
+        let started = Instant::now();
+        let results = terminate_many(&[polite_id.clone(), stubborn_id.clone()], false, Duration::from_millis(300));
+        assert_eq!(results[0], (polite.id(), TerminationOutcome::ExitedAfterTerm));
+        assert_eq!(results[1], (stubborn.id(), TerminationOutcome::StillAlive));
+        // 批量终止共用一个宽限期
+        assert!(started.elapsed() < Duration::from_secs(2));
+
+        assert_eq!(terminate_with_grace(&killable_id, true, Duration::from_millis(100)), TerminationOutcome::Killed);
+        assert_eq!(terminate_with_grace(&stubborn_id, true, Duration::ZERO), TerminationOutcome::Killed);
+        for child in [&mut polite, &mut stubborn, &mut killable] {
+            let _ = child.wait();
+        }
+
+        assert_eq!(terminate_with_grace(&polite_id, true, Duration::ZERO), TerminationOutcome::NotFound);
+        for pid in [0, u32::MAX] {
+            let bogus = ProcessIdentity { pid, start_time: None, exe: None };
+            assert_eq!(terminate_with_grace(&bogus, true, Duration::ZERO), TerminationOutcome::NotFound);
+        }
+        assert!(!TerminationOutcome::StillAlive.is_terminated());
+    }
+
+    #[test]
+    fn refuses_to_signal_a_reused_pid() {
+        let (mut child, identity) = spawn("exec sleep 30");
+        assert!(identity.start_time.is_some());
+
+        // 同一 PID 上启动时间或可执行文件不同的进程
+        let older = ProcessIdentity { start_time: identity.start_time.map(|t| t - 60), ..identity.clone() };
+        let other_exe = ProcessIdentity { exe: Some(PathBuf::from("/usr/bin/other")), ..identity.clone() };
+        for stale in [&older, &other_exe] {
+            assert_eq!(terminate_with_grace(stale, true, Duration::ZERO), TerminationOutcome::IdentityChanged);
+        }
+        assert!(process_alive(child.id()));
+
+        // 未知字段不参与比较
+        let pid_only = ProcessIdentity { pid: identity.pid, start_time: None, exe: None };
+        assert!(pid_only.matches(&identity));
+        assert_eq!(terminate_with_grace(&pid_only, true, Duration::from_millis(500)), TerminationOutcome::ExitedAfterTerm);
+        let _ = child.wait();
+    }
+
+    #[test]
+    fn polling_does_not_block() {
+        let (mut child, identity) = spawn("trap '' TERM; exec sleep 30");
+        let t0 = Instant::now();
+        let mut pending = PendingTermination::start_at(&identity, true, Duration::from_secs(60), t0);
+        assert_eq!(pending.poll(t0), None);
+        assert_eq!(pending.time_left(t0), Duration::from_secs(60));
+
+        // 宽限期结束后发送 SIGKILL，再等进程退出
+        let later = t0 + Duration::from_secs(60);
+        assert_eq!(pending.poll(later), None);
+        let _ = child.wait();
+        assert_eq!(pending.poll(later), Some(TerminationOutcome::Killed));
+    }
+}
@@ -0,0 +1,351 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use crate::cgroup::{group_by_cgroup, CgroupMemory};
+use crate::processes::{MemoryMetric, ProcessInfo};
+
+/// 进程的父进程是这些名字时，不再向上归并（它们负责启动应用，本身不属于任何应用）
+const LAUNCHERS: &[&str] = &[
+    "launchd",
+    "systemd",
+    "init",
+    "kthreadd",
+    "sshd",
+    "login",
+    "sudo",
+    "su",
+    "tmux",
+    "tmux: server",
+    "screen",
+    "sh",
+    "bash",
+    "zsh",
+    "fish",
+    "dash",
+    "Terminal",
+    "iTerm2",
+    "gnome-shell",
+    "gnome-terminal-server",
+    "konsole",
+    "plasmashell",
+    "kitty",
+    "alacritty",
+];
+
+/// 进程的聚合方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProcessGrouping {
+    /// 每个进程单独计算
+    #[default]
+    Process,
+    /// 辅助进程归并到所属应用
+    App,
+    /// 按 cgroup v2 归并（容器、systemd unit）
+    Cgroup,
+}
+
+impl std::fmt::Display for ProcessGrouping {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProcessGrouping::Process => write!(f, "process"),
+            ProcessGrouping::App => write!(f, "app"),
+            ProcessGrouping::Cgroup => write!(f, "cgroup"),
+        }
+    }
+}
+
+impl std::str::FromStr for ProcessGrouping {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "process" | "none" => Ok(ProcessGrouping::Process),
+            "app" => Ok(ProcessGrouping::App),
+            "cgroup" => Ok(ProcessGrouping::Cgroup),
+            other => Err(format!("Invalid grouping '{}', expected process, app or cgroup", other)),
+        }
+    }
+}
+
+/// 由父进程 PID 构建的进程树
+pub struct ProcessTree<'a> {
+    by_pid: HashMap<u32, &'a ProcessInfo>,
+    children: HashMap<u32, Vec<u32>>,
+}
+
+impl<'a> ProcessTree<'a> {
+    pub fn build(processes: &'a [ProcessInfo]) -> Self {
+        let by_pid: HashMap<u32, &ProcessInfo> = processes.iter().map(|p| (p.pid, p)).collect();
+        let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+        for p in processes {
+            if let Some(ppid) = p.ppid.filter(|&ppid| ppid != p.pid && by_pid.contains_key(&ppid)) {
+                children.entry(ppid).or_default().push(p.pid);
+            }
+        }
+        for pids in children.values_mut() {
+            pids.sort_unstable();
+        }
+        Self { by_pid, children }
+    }
+
+    pub fn get(&self, pid: u32) -> Option<&'a ProcessInfo> {
+        self.by_pid.get(&pid).copied()
+    }
+
+    pub fn children(&self, pid: u32) -> &[u32] {
+        self.children.get(&pid).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// pid 的全部后代（广度优先，不含 pid 本身）
+    pub fn descendants(&self, pid: u32) -> Vec<u32> {
+        let mut seen = HashSet::from([pid]);
+        let mut queue: VecDeque<u32> = self.children(pid).iter().copied().collect();
+        let mut result = Vec::new();
+        while let Some(child) = queue.pop_front() {
+            if !seen.insert(child) {
+                continue;
+            }
+            result.push(child);
+            queue.extend(self.children(child).iter().copied());
+        }
+        result
+    }
+
+    /// 向上找到所属应用的根进程：父进程是启动器、属于其他用户或不在快照中时停止
+    pub fn app_root(&self, pid: u32) -> u32 {
+        let mut current = pid;
+        let mut seen = HashSet::from([pid]);
+        while let Some(process) = self.get(current) {
+            let Some(parent) = process.ppid.and_then(|ppid| self.get(ppid)) else { break };
+            if parent.pid <= 1 || LAUNCHERS.contains(&parent.name.as_str()) {
+                break;
+            }
+            if let (Some(uid), Some(parent_uid)) = (process.uid, parent.uid) {
+                if uid != parent_uid {
+                    break;
+                }
+            }
+            if !seen.insert(parent.pid) {
+                // 父子关系成环（快照中 PID 被复用），环上的进程统一归到最小的 PID
+                return seen.into_iter().min().unwrap_or(pid);
+            }
+            current = parent.pid;
+        }
+        current
+    }
+}
+
+/// macOS 应用包名：可执行文件路径中最外层的 `*.app`
+pub fn bundle_name(exe: &Path) -> Option<String> {
+    exe.components()
+        .filter_map(|c| c.as_os_str().to_str())
+        .find_map(|c| c.strip_suffix(".app"))
+        .map(str::to_string)
+}
+
+/// 一个应用及其全部辅助进程
+#[derive(Debug, Clone, Serialize)]
+pub struct AppGroup {
+    pub name: String,
+    pub root_pid: u32,
+    pub rss_mb: u64,
+    /// 所有成员都有 PSS 时才汇总
+    pub pss_mb: Option<u64>,
+    pub uss_mb: Option<u64>,
+    pub cpu_usage: f32,
+    pub is_frontmost: bool,
+    /// 所有成员都空闲的时长（取最短的），有成员未跟踪时为 None
+    pub idle_secs: Option<u64>,
+    /// 根进程在前，其余按进程树顺序
+    pub processes: Vec<ProcessInfo>,
+    /// 按 cgroup 聚合时 cgroup 自身的内存统计（含页缓存）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cgroup_memory: Option<CgroupMemory>,
+}
+
+impl AppGroup {
+    pub(crate) fn new(name: String, members: Vec<ProcessInfo>) -> Self {
+        let sum = |f: fn(&ProcessInfo) -> Option<u64>| members.iter().map(f).sum::<Option<u64>>();
+        Self {
+            name,
+            root_pid: members[0].pid,
+            rss_mb: members.iter().map(|p| p.rss_mb).sum(),
+            pss_mb: sum(|p| p.pss_mb),
+            uss_mb: sum(|p| p.uss_mb),
+            cpu_usage: members.iter().map(|p| p.cpu_usage).sum(),
+            is_frontmost: members.iter().any(|p| p.is_frontmost),
+            idle_secs: members.iter().map(|p| p.idle_secs).min().flatten(),
+            processes: members,
+            cgroup_memory: None,
+        }
+    }
+
+    pub fn pids(&self) -> Vec<u32> {
+        self.processes.iter().map(|p| p.pid).collect()
+    }
+
+    /// 按指定口径汇总的内存，PSS/USS 不完整时退回 RSS
+    pub fn memory_mb(&self, metric: MemoryMetric) -> u64 {
+        match metric {
+            MemoryMetric::Rss => self.rss_mb,
+            MemoryMetric::Pss => self.pss_mb.unwrap_or(self.rss_mb),
+            MemoryMetric::Uss => self.uss_mb.unwrap_or(self.rss_mb),
+        }
+    }
+
+    /// 以根进程代表整个应用，内存和 CPU 换成汇总值（名称保持根进程名，便于执行前复核）
+    pub fn as_process(&self) -> ProcessInfo {
+        ProcessInfo {
+            rss_mb: self.rss_mb,
+            pss_mb: self.pss_mb,
+            uss_mb: self.uss_mb,
+            cpu_usage: self.cpu_usage,
+            is_frontmost: self.is_frontmost,
+            idle_secs: self.idle_secs,
+            ..self.processes[0].clone()
+        }
+    }
+}
+
+/// 把进程按应用聚合：先沿进程树归并到根进程，再把同一应用包下的多个根
+/// （例如由 launchd 启动的 XPC 服务）合并
+pub fn group_by_app(processes: &[ProcessInfo]) -> Vec<AppGroup> {
+    let tree = ProcessTree::build(processes);
+    let roots: HashMap<u32, u32> = processes.iter().map(|p| (p.pid, tree.app_root(p.pid))).collect();
+
+    let mut root_pids: Vec<u32> = roots.values().copied().collect();
+    root_pids.sort_unstable();
+    root_pids.dedup();
+
+    let mut order: Vec<String> = Vec::new();
+    let mut members: HashMap<String, (String, Vec<ProcessInfo>)> = HashMap::new();
+    for root in root_pids {
+        let Some(root_process) = tree.get(root).filter(|p| roots.get(&p.pid) == Some(&root)) else { continue };
+        let bundle = root_process.exe.as_deref().and_then(bundle_name);
+        let key = match &bundle {
+            Some(bundle) => format!("bundle:{}", bundle),
+            None => format!("pid:{}", root),
+        };
+
+        let (_, group) = members.entry(key.clone()).or_insert_with(|| {
+            order.push(key);
+            (bundle.unwrap_or_else(|| root_process.name.clone()), Vec::new())
+        });
+        group.push(root_process.clone());
+        // 只收归属于这个根的后代，启动器下的其他应用各自成组
+        group.extend(
+            tree.descendants(root)
+                .into_iter()
+                .filter(|pid| roots.get(pid) == Some(&root))
+                .filter_map(|pid| tree.get(pid))
+                .cloned(),
+        );
+    }
+
+    order.into_iter()
+        .filter_map(|key| members.remove(&key))
+        .map(|(name, group)| AppGroup::new(name, group))
+        .collect()
+}
+
+/// 按聚合方式分组；Process 时不分组，返回 None
+pub fn group_processes(processes: &[ProcessInfo], grouping: ProcessGrouping, cgroup_root: &Path) -> Option<Vec<AppGroup>> {
+    match grouping {
+        ProcessGrouping::Process => None,
+        ProcessGrouping::App => Some(group_by_app(processes)),
+        ProcessGrouping::Cgroup => Some(group_by_cgroup(processes, cgroup_root)),
+    }
+}
+
+/// 按指定口径降序取前 n 个应用
+pub fn sort_and_take_groups(mut groups: Vec<AppGroup>, n: usize, metric: MemoryMetric) -> Vec<AppGroup> {
+    groups.sort_by_key(|g| std::cmp::Reverse(g.memory_mb(metric)));
+    groups.into_iter().take(n).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn process(pid: u32, ppid: u32, name: &str, rss_mb: u64, exe: Option<&str>) -> ProcessInfo {
+        ProcessInfo {
+            pid,
+            ppid: Some(ppid),
+            name: name.to_string(),
+            rss_mb,
+            uid: Some(501),
+            exe: exe.map(PathBuf::from),
+            ..Default::default()
+        }
+    }
+
+    fn browser_session() -> Vec<ProcessInfo> {
+        let chrome = "/Applications/Google Chrome.app/Contents/MacOS/Google Chrome";
+        let helper = "/Applications/Google Chrome.app/Contents/Frameworks/Google Chrome Framework.framework/Helpers/Google Chrome Helper.app/Contents/MacOS/Google Chrome Helper";
+        vec![
+            process(1, 0, "launchd", 20, None),
+            process(400, 1, "Google Chrome", 500, Some(chrome)),
+            process(401, 400, "Google Chrome Helper", 300, Some(helper)),
+            process(402, 400, "Google Chrome Helper (GPU)", 200, Some(helper)),
+            process(403, 402, "Google Chrome Helper (Renderer)", 100, Some(helper)),
+            // 由 launchd 启动但属于同一应用包的 XPC 服务
+            process(410, 1, "chrome_crashpad_handler", 10, Some(helper)),
+            process(500, 1, "Terminal", 80, None),
+            process(501, 500, "zsh", 5, None),
+            process(502, 501, "cargo", 150, None),
+            process(503, 502, "rustc", 900, None),
+        ]
+    }
+
+    #[test]
+    fn builds_tree_from_parent_pids() {
+        let processes = browser_session();
+        let tree = ProcessTree::build(&processes);
+        assert_eq!(tree.children(400), &[401, 402]);
+        assert_eq!(tree.descendants(400), vec![401, 402, 403]);
+        assert_eq!(tree.app_root(403), 400);
+        // shell 是启动器，cargo 及其子进程单独成组
+        assert_eq!(tree.app_root(503), 502);
+        assert_eq!(tree.app_root(501), 501);
+    }
+
+    #[test]
+    fn groups_helpers_under_their_app() {
+        let groups = group_by_app(&browser_session());
+        let chrome = groups.iter().find(|g| g.name == "Google Chrome").unwrap();
+        assert_eq!(chrome.root_pid, 400);
+        assert_eq!(chrome.pids(), vec![400, 401, 402, 403, 410]);
+        assert_eq!(chrome.rss_mb, 1110);
+        assert_eq!(chrome.as_process().pid, 400);
+        assert_eq!(chrome.as_process().rss_mb, 1110);
+
+        let cargo = groups.iter().find(|g| g.root_pid == 502).unwrap();
+        assert_eq!(cargo.name, "cargo");
+        assert_eq!(cargo.pids(), vec![502, 503]);
+
+        let top = sort_and_take_groups(groups, 1, MemoryMetric::Rss);
+        assert_eq!(top[0].name, "Google Chrome");
+    }
+
+    #[test]
+    fn tolerates_cycles_and_missing_parents() {
+        let processes = vec![
+            process(10, 11, "a", 1, None),
+            process(11, 10, "b", 1, None),
+            process(20, 999, "orphan", 1, None),
+        ];
+        let tree = ProcessTree::build(&processes);
+        assert_eq!(tree.app_root(10), 10);
+        assert_eq!(tree.app_root(11), 10);
+        assert_eq!(tree.app_root(20), 20);
+        assert!(tree.descendants(10).len() <= 1);
+        let groups = group_by_app(&processes);
+        assert_eq!(groups.iter().map(|g| g.processes.len()).sum::<usize>(), 3);
+
+        let launchd = group_by_app(&browser_session()).into_iter().find(|g| g.root_pid == 1).unwrap();
+        assert_eq!(launchd.pids(), vec![1]);
+        assert_eq!(bundle_name(Path::new("/usr/bin/python3")), None);
+    }
+}
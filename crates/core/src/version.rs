@@ -45,7 +45,7 @@ pub fn get_current_version() -> String {
 /// 从GitHub API检查最新版本
 pub fn check_latest_version() -> Result<String, UpdateError> {
     let output = Command::new("curl")
-        .args(&[
+        .args([
             "-s",
             "-H", "Accept: application/vnd.github.v3+json",
             "https://api.github.com/repos/ink1ing/rambooster/releases/latest"
@@ -144,7 +144,7 @@ pub fn cleanup_old_versions() -> Result<Vec<String>, UpdateError> {
                         let file_name_str = file_name.to_string_lossy();
                         if file_name_str.starts_with("rb.backup.") {
                             let full_path = entry.path();
-                            if let Ok(_) = fs::remove_file(&full_path) {
+                            if fs::remove_file(&full_path).is_ok() {
                                 cleaned_files.push(full_path.to_string_lossy().to_string());
                             }
                         }
@@ -159,11 +159,10 @@ pub fn cleanup_old_versions() -> Result<Vec<String>, UpdateError> {
                     // 这可能是旧版本，但要小心不要删除当前版本
                     let current_exe = std::env::current_exe().ok();
                     if let Some(current_path) = current_exe {
-                        if current_path.to_string_lossy() != *location {
-                            if let Ok(_) = fs::remove_file(location) {
+                        if current_path.to_string_lossy() != *location
+                            && fs::remove_file(location).is_ok() {
                                 cleaned_files.push(location.to_string());
                             }
-                        }
                     }
                 }
             }
@@ -62,6 +62,7 @@ fn test_log_event_write_and_read() {
         throttle_interval_seconds: 300,
         whitelist_processes: vec![],
         blacklist_processes: vec![],
+        ..Config::default()
     };
 
     // 创建测试用的内存统计数据
@@ -72,6 +73,9 @@ fn test_log_event_write_and_read() {
         inactive_mb: 4000,
         wired_mb: 2384,
         compressed_mb: 2000,
+        available_mb: 6000,
+        psi: None,
+        swap: Default::default(),
         pressure: PressureLevel::Normal,
     };
 
@@ -82,6 +86,9 @@ fn test_log_event_write_and_read() {
         inactive_mb: 4000,
         wired_mb: 2384,
         compressed_mb: 2000,
+        available_mb: 6500,
+        psi: None,
+        swap: Default::default(),
         pressure: PressureLevel::Normal,
     };
 
@@ -143,11 +150,7 @@ fn test_memory_stats_consistency() {
 
     // 基本合理性检查
     assert!(stats.total_mb > 0, "Total memory should be positive");
-    assert!(stats.free_mb >= 0, "Free memory should be non-negative");
-    assert!(stats.active_mb >= 0, "Active memory should be non-negative");
-    assert!(stats.inactive_mb >= 0, "Inactive memory should be non-negative");
-    assert!(stats.wired_mb >= 0, "Wired memory should be non-negative");
-    assert!(stats.compressed_mb >= 0, "Compressed memory should be non-negative");
+    // 其余字段为无符号整数，非负由类型保证
 
     // 验证内存总和不超过总内存（在合理范围内）
     let used_memory = stats.active_mb + stats.inactive_mb + stats.wired_mb;
@@ -237,12 +240,10 @@ fn test_log_file_cleanup_simulation() {
         // 检查日志文件
         if let Ok(entries) = fs::read_dir(log_path) {
             let mut log_files = Vec::new();
-            for entry in entries {
-                if let Ok(entry) = entry {
-                    let path = entry.path();
-                    if path.extension().and_then(|s| s.to_str()) == Some("jsonl") {
-                        log_files.push(path);
-                    }
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.extension().and_then(|s| s.to_str()) == Some("jsonl") {
+                    log_files.push(path);
                 }
             }
 